@@ -0,0 +1,163 @@
+//! Compares [TaggedCell] with `once_cell`, `lazy_static` and [OnceLock] on:
+//! - uncontended access to an initialized static
+//! - the first initialization of a fresh cell, raced by several threads
+//! - access in a tight loop, where the compiler can't hoist the state check out of the loop
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use lazy_static::lazy_static;
+use once_cell::sync::{Lazy, OnceCell};
+use std::{
+    hint::black_box,
+    sync::{Barrier, OnceLock},
+    thread,
+    time::{Duration, Instant},
+};
+use tagged_cell::{tagged_cell, TaggedCell};
+
+tagged_cell! {
+    static TAGGED: TaggedCell<usize, _> = TaggedCell::new();
+}
+static ONCE_LOCK: OnceLock<usize> = OnceLock::new();
+static ONCE_CELL: OnceCell<usize> = OnceCell::new();
+static LAZY: Lazy<usize> = Lazy::new(|| 1);
+lazy_static! {
+    static ref LAZY_STATIC: usize = 1;
+}
+
+/// Length of the slice summed by the tight loop benchmarks
+const LOOP_LEN: usize = 1024;
+/// Thread counts racing to initialize a cell
+const THREADS: [usize; 3] = [2, 4, 8];
+
+fn access(c: &mut Criterion) {
+    let tag = TAGGED.init(|| 1);
+    ONCE_LOCK.get_or_init(|| 1);
+    ONCE_CELL.get_or_init(|| 1);
+
+    // `get` rather than `get_or_init`, the cheapest access the other cells offer
+    let mut group = c.benchmark_group("access");
+    group.bench_function("tagged_cell", |b| b.iter(|| *black_box(&TAGGED).get(tag)));
+    group.bench_function("once_lock", |b| {
+        b.iter(|| *black_box(&ONCE_LOCK).get().unwrap())
+    });
+    group.bench_function("once_cell", |b| {
+        b.iter(|| *black_box(&ONCE_CELL).get().unwrap())
+    });
+    group.bench_function("once_cell_lazy", |b| b.iter(|| **black_box(&LAZY)));
+    group.bench_function("lazy_static", |b| b.iter(|| **black_box(&LAZY_STATIC)));
+    group.finish();
+}
+
+/// Time `threads` threads racing to initialize a fresh cell with `init`, released together by a
+/// barrier. Each iteration counts the slowest thread, the one that waited on the winner longest
+fn race<C: Sync>(
+    iters: u64,
+    threads: usize,
+    new: impl Fn() -> C,
+    init: impl Fn(&C) + Sync,
+) -> Duration {
+    let mut total = Duration::ZERO;
+    for _ in 0..iters {
+        let cell = new();
+        let barrier = Barrier::new(threads);
+        total += thread::scope(|s| {
+            let handles: Vec<_> = (0..threads)
+                .map(|_| {
+                    s.spawn(|| {
+                        barrier.wait();
+                        let start = Instant::now();
+                        init(&cell);
+                        start.elapsed()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .max()
+                .unwrap()
+        });
+    }
+    total
+}
+
+fn contended_init(c: &mut Criterion) {
+    struct Tag;
+
+    let mut group = c.benchmark_group("contended_init");
+    for threads in THREADS {
+        group.bench_with_input(
+            BenchmarkId::new("tagged_cell", threads),
+            &threads,
+            |b, &n| {
+                b.iter_custom(|iters| {
+                    race(
+                        iters,
+                        n,
+                        || unsafe { TaggedCell::<usize, Tag>::new() },
+                        |cell| {
+                            black_box(cell.get(cell.init(|| 1)));
+                        },
+                    )
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("once_lock", threads), &threads, |b, &n| {
+            b.iter_custom(|iters| {
+                race(iters, n, OnceLock::<usize>::new, |cell| {
+                    black_box(cell.get_or_init(|| 1));
+                })
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("once_cell", threads), &threads, |b, &n| {
+            b.iter_custom(|iters| {
+                race(iters, n, OnceCell::<usize>::new, |cell| {
+                    black_box(cell.get_or_init(|| 1));
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+fn tight_loop(c: &mut Criterion) {
+    let tag = TAGGED.init(|| 1);
+    let data: Vec<usize> = (0..LOOP_LEN).collect();
+
+    // `black_box` on the static inside the loop keeps the compiler from reading it only once
+    let mut group = c.benchmark_group("tight_loop");
+    group.bench_function("tagged_cell", |b| {
+        b.iter(|| {
+            data.iter()
+                .map(|x| x * black_box(&TAGGED).get(tag))
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("once_lock", |b| {
+        b.iter(|| {
+            data.iter()
+                .map(|x| x * black_box(&ONCE_LOCK).get_or_init(|| 1))
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("once_cell", |b| {
+        b.iter(|| {
+            data.iter()
+                .map(|x| x * black_box(&ONCE_CELL).get_or_init(|| 1))
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("once_cell_lazy", |b| {
+        b.iter(|| data.iter().map(|x| x * **black_box(&LAZY)).sum::<usize>())
+    });
+    group.bench_function("lazy_static", |b| {
+        b.iter(|| {
+            data.iter()
+                .map(|x| x * **black_box(&LAZY_STATIC))
+                .sum::<usize>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, access, contended_init, tight_loop);
+criterion_main!(benches);
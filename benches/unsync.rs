@@ -0,0 +1,65 @@
+//! Compares [unsync::TaggedCell] with the thread safe [TaggedCell], on the first initialization
+//! of a fresh cell, later calls to `init`, and `get`
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use std::hint::black_box;
+use tagged_cell::{unsync, TaggedCell};
+
+struct SyncTag;
+struct UnsyncTag;
+
+fn first_init(c: &mut Criterion) {
+    let mut group = c.benchmark_group("first_init");
+    group.bench_function("sync", |b| {
+        b.iter_batched(
+            // SAFETY: a batch holds several cells tagged `SyncTag` at once, which is sound as
+            // their `Init` tags are only passed to `black_box`, never used to read another cell
+            || unsafe { TaggedCell::<usize, SyncTag>::new() },
+            |cell| {
+                black_box(cell.init(|| 1));
+                cell
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("unsync", |b| {
+        b.iter_batched(
+            // SAFETY: as above, no `Init` tag is used to read any of the batch's cells
+            || unsafe { unsync::TaggedCell::<usize, UnsyncTag>::new() },
+            |cell| {
+                black_box(cell.init(|| 1));
+                cell
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn init(c: &mut Criterion) {
+    // SAFETY: these are the only cells with their tags until the end of the function
+    let sync = unsafe { TaggedCell::<usize, SyncTag>::new() };
+    let unsync = unsafe { unsync::TaggedCell::<usize, UnsyncTag>::new() };
+    sync.init(|| 1);
+    unsync.init(|| 1);
+
+    let mut group = c.benchmark_group("init");
+    group.bench_function("sync", |b| b.iter(|| black_box(&sync).init(|| 2)));
+    group.bench_function("unsync", |b| b.iter(|| black_box(&unsync).init(|| 2)));
+    group.finish();
+}
+
+fn get(c: &mut Criterion) {
+    // SAFETY: these are the only cells with their tags until the end of the function
+    let sync = unsafe { TaggedCell::<usize, SyncTag>::new() };
+    let unsync = unsafe { unsync::TaggedCell::<usize, UnsyncTag>::new() };
+    let sync_tag = sync.init(|| 1);
+    let unsync_tag = unsync.init(|| 1);
+
+    let mut group = c.benchmark_group("get");
+    group.bench_function("sync", |b| b.iter(|| *black_box(&sync).get(sync_tag)));
+    group.bench_function("unsync", |b| b.iter(|| *black_box(&unsync).get(unsync_tag)));
+    group.finish();
+}
+
+criterion_group!(benches, first_init, init, get);
+criterion_main!(benches);
@@ -0,0 +1,230 @@
+//! Fixed size array of [TaggedCell]s, each slot initialized on its own
+use crate::{Init, InitError, Proves, TaggedCell};
+use core::marker::PhantomData;
+
+/// An array of `N` cells sharing one tag type, for per-shard or per-device state where each slot
+/// is initialized independently on first use. Use [tagged_cell!][crate::tagged_cell] with
+/// `TaggedCellArray::new()` to make this struct.
+///
+/// Slots picked at runtime are initialized with [init()][TaggedCellArray::init], which returns an
+/// [IndexInit] carrying the slot's index. The index is checked once by `init`, so
+/// [get()][TaggedCellArray::get] performs no check at all. Slots known at compile time can use
+/// [init_at()][TaggedCellArray::init_at] instead, whose zero-sized [Init] proof names the slot in
+/// its type, and combines with other proofs like any [Init]
+/// ```
+/// use tagged_cell::{tagged_cell, Init, Slot};
+///
+/// tagged_cell!{
+///     static SHARDS: TaggedCellArray<Vec<u64>, ShardsTag, 4> = TaggedCellArray::new();
+/// }
+///
+/// let shard = SHARDS.init(2, Vec::new);
+/// assert_eq!(shard.index(), 2);
+/// assert!(SHARDS.get(shard).is_empty());
+///
+/// fn primary(tag: Init<Slot<ShardsTag, 0>>) -> usize {
+///     SHARDS.get_at(tag).len()
+/// }
+/// assert_eq!(primary(SHARDS.init_at::<0, _>(|| vec![1, 2])), 2);
+/// ```
+/// Compile time slots are checked against the length of the array. The check runs when the call
+/// is compiled to code, so `cargo build` rejects an index out of bounds while `cargo check` doesn't
+/// ```compile_fail
+/// use tagged_cell::tagged_cell;
+///
+/// tagged_cell!{
+///     static SHARDS: TaggedCellArray<usize, _, 4> = TaggedCellArray::new();
+/// }
+///
+/// SHARDS.init_at::<4, _>(|| 0);
+/// ```
+pub struct TaggedCellArray<T, Tag, const N: usize> {
+    slots: [TaggedCell<T, Tag>; N],
+}
+
+/// Tag type of the slot at index `I` of a [TaggedCellArray] with tag `Tag`. `Init<Slot<Tag, I>>`
+/// proves that slot is initialized
+pub struct Slot<Tag, const I: usize> {
+    tag: PhantomData<Tag>,
+}
+
+/// A marker proving that the slot at [index()][IndexInit::index] of the unique
+/// [TaggedCellArray] with tag `Tag` is initialized. Like [Init], it can't be sent to other threads
+pub struct IndexInit<Tag> {
+    index: usize,
+    tag: PhantomData<(Tag, *const ())>,
+}
+
+// Implemented by hand, deriving would require `Tag: Copy`
+impl<Tag> Clone for IndexInit<Tag> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Tag> Copy for IndexInit<Tag> {}
+
+impl<Tag> IndexInit<Tag> {
+    /// Index of the initialized slot
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<Tag, const I: usize> From<Init<Slot<Tag, I>>> for IndexInit<Tag> {
+    /// Forget the slot's index at the type level, keeping it at runtime
+    fn from(_: Init<Slot<Tag, I>>) -> Self {
+        IndexInit {
+            index: I,
+            tag: PhantomData,
+        }
+    }
+}
+
+impl<T, Tag, const N: usize> TaggedCellArray<T, Tag, N> {
+    /// Internal method to create an array of uninitialized slots. Unsafe for the same reasons as
+    /// [TaggedCell::new], use [tagged_cell!][crate::tagged_cell] for safe [TaggedCellArray]
+    /// creation
+    #[cfg(not(loom))]
+    #[doc(hidden)]
+    pub const unsafe fn new() -> Self {
+        TaggedCellArray {
+            slots: [const { unsafe { TaggedCell::new() } }; N],
+        }
+    }
+
+    /// Internal method to create an array of uninitialized slots
+    #[cfg(loom)]
+    #[doc(hidden)]
+    pub unsafe fn new() -> Self {
+        TaggedCellArray {
+            slots: core::array::from_fn(|_| unsafe { TaggedCell::new() }),
+        }
+    }
+
+    /// Initialize the slot at `index`, if not already initialized, using the provided function or
+    /// closure. Returns a tag carrying the index, required to access the slot's data. Each slot
+    /// behaves like its own [TaggedCell::init].
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds, or for the same reasons as [TaggedCell::init]
+    pub fn init<F>(&self, index: usize, f: F) -> IndexInit<Tag>
+    where
+        F: FnOnce() -> T,
+    {
+        self.slot(index).init(f);
+        IndexInit {
+            index,
+            tag: PhantomData,
+        }
+    }
+
+    /// Fallible version of [init()][TaggedCellArray::init], behaves like [TaggedCell::try_init]
+    /// for the slot at `index`
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds
+    pub fn try_init<F, E>(&self, index: usize, f: F) -> Result<IndexInit<Tag>, InitError<E>>
+    where
+        F: FnOnce() -> Result<T, E>,
+    {
+        self.slot(index).try_init(f)?;
+        Ok(IndexInit {
+            index,
+            tag: PhantomData,
+        })
+    }
+
+    /// Get the data of the slot named by `proof` (obtained via [TaggedCellArray::init]). Neither
+    /// the index nor the slot's state is checked
+    pub fn get(&self, proof: IndexInit<Tag>) -> &T {
+        // SAFETY: `init` only returns a proof after checking its index is in bounds
+        let slot = unsafe { self.slots.get_unchecked(proof.index) };
+        slot.get(Init::<Tag> { tag: PhantomData })
+    }
+
+    /// If the slot at `index` exists and has already been initialized, returns a tag for it along
+    /// with its data
+    pub fn try_get(&self, index: usize) -> Option<(IndexInit<Tag>, &T)> {
+        let (_, val) = self.slots.get(index)?.try_get()?;
+        let tag = IndexInit {
+            index,
+            tag: PhantomData,
+        };
+        Some((tag, val))
+    }
+
+    /// Initialize the slot at the compile time index `I`, if not already initialized. Returns a
+    /// zero-sized tag for that slot. Indices out of bounds fail to build, though as the check is
+    /// only evaluated once the call is monomorphized, `cargo check` doesn't report them
+    pub fn init_at<const I: usize, F>(&self, f: F) -> Init<Slot<Tag, I>>
+    where
+        F: FnOnce() -> T,
+    {
+        const { assert!(I < N, "slot index out of bounds") };
+        self.slots[I].init(f);
+        Init { tag: PhantomData }
+    }
+
+    /// Get the data of the slot at the compile time index `I`, requires a tag (obtained via
+    /// [TaggedCellArray::init_at]) to perform the access. A composite proof containing the slot's
+    /// tag is also accepted. Indices are checked like [init_at()][TaggedCellArray::init_at]
+    pub fn get_at<const I: usize, P, X>(&self, _: P) -> &T
+    where
+        P: Proves<Slot<Tag, I>, X>,
+    {
+        const { assert!(I < N, "slot index out of bounds") };
+        self.slots[I].get(Init::<Tag> { tag: PhantomData })
+    }
+
+    /// Returns true if the slot at `index` exists and has been successfully initialized
+    pub fn is_initialized(&self, index: usize) -> bool {
+        self.slots
+            .get(index)
+            .is_some_and(TaggedCell::is_initialized)
+    }
+
+    /// Number of slots in the array
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns true if the array has no slots
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Drop the data of every initialized slot, see [TaggedCell::reset]. Requires the `testing`
+    /// feature
+    ///
+    /// # Safety
+    /// See [TaggedCell::reset], for every slot of the array
+    #[cfg(feature = "testing")]
+    pub unsafe fn reset(&self) {
+        for slot in &self.slots {
+            slot.reset();
+        }
+    }
+
+    /// Replace the value of the slot at `index` on the current thread, until the returned guard
+    /// is dropped, see [TaggedCell::override_for_scope]. Requires the `testing` feature
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds
+    #[cfg(feature = "testing")]
+    pub fn override_for_scope(&self, index: usize, value: T) -> crate::OverrideGuard<'_, T, Tag> {
+        self.slot(index).override_for_scope(value)
+    }
+
+    fn slot(&self, index: usize) -> &TaggedCell<T, Tag> {
+        match self.slots.get(index) {
+            Some(slot) => slot,
+            None => panic!(
+                "index {} out of bounds for TaggedCellArray `{}` of length {}",
+                index,
+                core::any::type_name::<Tag>(),
+                N
+            ),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! [TaggedCell][crate::TaggedCell] that can only be initialized after its dependencies
+use crate::{Init, InitError, Proves, TaggedCell};
+use core::marker::PhantomData;
+
+/// A [TaggedCell] declaring the cells it depends on, by the tag type `Deps`, or a tuple of tags
+/// for several dependencies. It has no plain [init()][TaggedCell::init], the only way to
+/// initialize it is [init_with()][TaggedDependent::init_with], which requires the [Init] proofs
+/// of its dependencies and hands them to the initializer. Initialization order is then checked
+/// by the compiler. Use [tagged_cell!][crate::tagged_cell] with `TaggedCell::requires(...)` to
+/// make this struct
+/// ```compile_fail
+/// use tagged_cell::tagged_cell;
+///
+/// tagged_cell!{
+///     static CONFIG: TaggedCell<&'static str, ConfigTag> = TaggedCell::new();
+///     static DB: TaggedCell<String, _> = TaggedCell::requires(ConfigTag);
+/// }
+///
+/// // DB can't be initialized without proof that CONFIG is
+/// DB.init(|| String::from("db"));
+/// ```
+pub struct TaggedDependent<T, Tag, Deps> {
+    cell: TaggedCell<T, Tag>,
+    deps: PhantomData<fn() -> Deps>,
+}
+
+impl<T, Tag, Deps> TaggedDependent<T, Tag, Deps> {
+    loom_const_fn! {
+        /// Internal method to create an uninitialized dependent cell. Unsafe for the same reasons
+        /// as [TaggedCell::new], use [tagged_cell!][crate::tagged_cell] for safe
+        /// [TaggedDependent] creation
+        #[doc(hidden)]
+        pub unsafe fn new() -> Self {
+            TaggedDependent {
+                cell: TaggedCell::new(),
+                deps: PhantomData,
+            }
+        }
+    }
+
+    /// Initialize the cell, if not already initialized, given proof that its dependencies are
+    /// initialized. The initializer receives that proof, to access the dependencies' data.
+    /// Behaves like [TaggedCell::init] otherwise
+    pub fn init_with<P, I, F>(&self, deps: P, f: F) -> Init<Tag>
+    where
+        P: Proves<Deps, I>,
+        F: FnOnce(Init<Deps>) -> T,
+    {
+        let deps = deps.proof();
+        self.cell.init(move || f(deps))
+    }
+
+    /// Fallible version of [init_with()][TaggedDependent::init_with], behaves like
+    /// [TaggedCell::try_init]
+    pub fn try_init_with<P, I, F, E>(&self, deps: P, f: F) -> Result<Init<Tag>, InitError<E>>
+    where
+        P: Proves<Deps, I>,
+        F: FnOnce(Init<Deps>) -> Result<T, E>,
+    {
+        let deps = deps.proof();
+        self.cell.try_init(move || f(deps))
+    }
+
+    /// Get the data within a [TaggedDependent], requires a tag (obtained via
+    /// [TaggedDependent::init_with]) to perform the access
+    pub fn get<P, I>(&self, proof: P) -> &T
+    where
+        P: Proves<Tag, I>,
+    {
+        self.cell.get(proof)
+    }
+
+    /// See [TaggedCell::try_get]
+    pub fn try_get(&self) -> Option<(Init<Tag>, &T)> {
+        self.cell.try_get()
+    }
+
+    /// Returns true if the cell has been successfully initialized
+    pub fn is_initialized(&self) -> bool {
+        self.cell.is_initialized()
+    }
+
+    /// See [TaggedCell::reset]
+    ///
+    /// # Safety
+    /// See [TaggedCell::reset]
+    #[cfg(feature = "testing")]
+    pub unsafe fn reset(&self) {
+        self.cell.reset()
+    }
+
+    /// See [TaggedCell::override_for_scope]
+    #[cfg(feature = "testing")]
+    pub fn override_for_scope(&self, value: T) -> crate::OverrideGuard<'_, T, Tag> {
+        self.cell.override_for_scope(value)
+    }
+}
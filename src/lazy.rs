@@ -0,0 +1,87 @@
+//! [TaggedCell][crate::TaggedCell] that stores its own initializer
+use crate::{Init, Proves, TaggedCell};
+use core::ops::Deref;
+
+/// A [TaggedCell] paired with the initializer it is always initialized with. This gives a single
+/// source of truth for the cell's value, rather than repeating the initializer at every
+/// [init()][TaggedCell::init] call site. It also dereferences to its data, initializing it on
+/// first access. Use [tagged_cell!][crate::tagged_cell] with `TaggedCell::lazy(|| ...)` to make
+/// this struct
+pub struct TaggedLazy<T, Tag, F = fn() -> T> {
+    cell: TaggedCell<T, Tag>,
+    init: F,
+}
+
+impl<T, Tag, F> TaggedLazy<T, Tag, F> {
+    loom_const_fn! {
+        /// Internal method to create an uninitialized lazy cell. Unsafe for the same reasons as
+        /// [TaggedCell::new], use [tagged_cell!][crate::tagged_cell] for safe [TaggedLazy]
+        /// creation
+        #[doc(hidden)]
+        pub unsafe fn new(init: F) -> Self {
+            TaggedLazy {
+                cell: TaggedCell::new(),
+                init,
+            }
+        }
+    }
+
+    /// Get the data within a [TaggedLazy], requires a tag (obtained via [TaggedLazy::init]) to
+    /// perform the access. A composite proof containing the cell's tag is also accepted
+    pub fn get<P, I>(&self, proof: P) -> &T
+    where
+        P: Proves<Tag, I>,
+    {
+        self.cell.get(proof)
+    }
+
+    /// Returns true if the cell has been successfully initialized
+    pub fn is_initialized(&self) -> bool {
+        self.cell.is_initialized()
+    }
+
+    /// See [TaggedCell::reset]
+    ///
+    /// # Safety
+    /// See [TaggedCell::reset]
+    #[cfg(feature = "testing")]
+    pub unsafe fn reset(&self) {
+        self.cell.reset()
+    }
+
+    /// See [TaggedCell::override_for_scope]
+    #[cfg(feature = "testing")]
+    pub fn override_for_scope(&self, value: T) -> crate::OverrideGuard<'_, T, Tag> {
+        self.cell.override_for_scope(value)
+    }
+}
+
+impl<T, Tag, F> TaggedLazy<T, Tag, F>
+where
+    F: Fn() -> T,
+{
+    /// Initialize the cell with its stored initializer, if not already initialized, and return
+    /// the tag required to access the data. Behaves like [TaggedCell::init] otherwise
+    pub fn init(&self) -> Init<Tag> {
+        self.cell.init(&self.init)
+    }
+
+    /// Same as [init()][TaggedLazy::init]. Reads better where a cell accessed through [Deref] hands
+    /// out its proof, to switch a hot path over to the unchecked [get()][TaggedLazy::get]
+    pub fn proof(&self) -> Init<Tag> {
+        self.init()
+    }
+}
+
+impl<T, Tag, F> Deref for TaggedLazy<T, Tag, F>
+where
+    F: Fn() -> T,
+{
+    type Target = T;
+
+    /// Initialize the cell if needed and return its data. Unlike [get()][TaggedLazy::get], this
+    /// checks whether the cell is initialized on every access
+    fn deref(&self) -> &T {
+        self.get(self.init())
+    }
+}
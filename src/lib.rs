@@ -1,32 +1,167 @@
 #![doc = include_str!("../README.md")]
-use std::{cell::UnsafeCell, marker::PhantomData, mem::MaybeUninit, sync::Once};
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+use core::{
+    any::type_name,
+    convert::Infallible,
+    future::{poll_fn, Future},
+    marker::PhantomData,
+    mem::MaybeUninit,
+    pin::pin,
+};
+
+#[macro_use]
+mod sync;
+
+mod array;
+mod dependent;
+mod lazy;
+mod once;
+mod poison;
+mod proof;
+#[cfg(feature = "testing")]
+mod testing;
+#[cfg(feature = "std")]
+mod thread_local;
+pub mod unsync;
+
+use once::{Begin, CallError, Once};
+use sync::UnsafeCell;
+pub use array::{IndexInit, Slot, TaggedCellArray};
+pub use dependent::TaggedDependent;
+pub use lazy::TaggedLazy;
+pub use poison::{InitError, PoisonError, PoisonPolicy};
+pub use proof::{Elem, Here, Proves};
+#[cfg(feature = "testing")]
+pub use testing::OverrideGuard;
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub use thread_local::LocalSlot;
+
+/// Attribute macro alternative to [tagged_cell!], enabled by the `macros` feature.
+/// It lives in its own module, as it can't share a name with the declarative macro at the crate
+/// root
+#[cfg(feature = "macros")]
+pub mod macros {
+    pub use tagged_cell_macros::tagged_cell;
+}
+#[cfg(feature = "std")]
+pub use thread_local::{LocalInit, TaggedThreadLocal};
 
 /// Top level structure to support initializable and thread safe static variables.
 /// Use [tagged_cell!] macro to make this struct
 pub struct TaggedCell<T, Tag> {
     once: Once,
+    policy: PoisonPolicy,
     tag: PhantomData<Tag>,
     data: UnsafeCell<MaybeUninit<T>>,
+    #[cfg(feature = "testing")]
+    override_id: testing::OverrideId,
 }
 
 /// A marker proving that the unique cell with tag `Tag` is initialized.
 /// This cannot be sent across threads, the only way to obtain it is by running
 /// [init()][TaggedCell::init] in the current thread
-#[derive(Clone, Copy)]
+/// ```compile_fail
+/// use tagged_cell::tagged_cell;
+///
+/// tagged_cell!{
+///     static FOO: TaggedCell<usize, _> = TaggedCell::new();
+/// }
+///
+/// let tag = FOO.init(|| 1);
+/// std::thread::spawn(move || *FOO.get(tag));
+/// ```
+/// To hand a proof to another thread explicitly, convert it into a [SendInit]
 pub struct Init<Tag> {
+    tag: PhantomData<(Tag, *const ())>,
+}
+
+// Implemented by hand, deriving would require `Tag: Copy`
+impl<Tag> Clone for Init<Tag> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Tag> Copy for Init<Tag> {}
+
+/// A sendable version of [Init], created with [Init::into_send]. Sending a proof to another
+/// thread is sound, as whatever mechanism moves it there also synchronizes with the thread that
+/// initialized the cell. It is converted back with [SendInit::into_local] to access the data
+/// ```
+/// use tagged_cell::tagged_cell;
+///
+/// tagged_cell!{
+///     static FOO: TaggedCell<usize, _> = TaggedCell::new();
+/// }
+///
+/// let tag = FOO.init(|| 1).into_send();
+/// let num = std::thread::spawn(move || *FOO.get(tag.into_local())).join().unwrap();
+/// assert_eq!(num, 1);
+/// ```
+pub struct SendInit<Tag> {
     tag: PhantomData<Tag>,
 }
 
+impl<Tag> Clone for SendInit<Tag> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Tag> Copy for SendInit<Tag> {}
+
+impl<Tag> Init<Tag> {
+    /// Opt in to sending this proof to other threads
+    pub fn into_send(self) -> SendInit<Tag> {
+        SendInit { tag: PhantomData }
+    }
+}
+
+impl<Tag> SendInit<Tag> {
+    /// Convert back into an [Init] tag bound to the current thread
+    pub fn into_local(self) -> Init<Tag> {
+        Init { tag: PhantomData }
+    }
+}
+
+impl<Tag> From<Init<Tag>> for SendInit<Tag> {
+    fn from(init: Init<Tag>) -> Self {
+        init.into_send()
+    }
+}
+
+impl<Tag> From<SendInit<Tag>> for Init<Tag> {
+    fn from(init: SendInit<Tag>) -> Self {
+        init.into_local()
+    }
+}
+
 impl<T, Tag> TaggedCell<T, Tag> {
-    /// Internal method to create an uninitialized cell. This relies on the user to define a unique
-    /// 'Tag' type for each call to new, and and thus is listed as unsafe. Use [tagged_cell!] for
-    /// safe [TaggedCell] creation
-    #[doc(hidden)]
-    pub const unsafe fn new() -> Self {
-        TaggedCell {
-            data: UnsafeCell::new(MaybeUninit::<T>::uninit()),
-            tag: PhantomData,
-            once: Once::new(),
+    loom_const_fn! {
+        /// Internal method to create an uninitialized cell. This relies on the user to define a
+        /// unique 'Tag' type for each call to new, and and thus is listed as unsafe. Use
+        /// [tagged_cell!] for safe [TaggedCell] creation
+        #[doc(hidden)]
+        pub unsafe fn new() -> Self {
+            Self::with_policy(PoisonPolicy::Poison)
+        }
+    }
+
+    loom_const_fn! {
+        /// Internal method to create an uninitialized cell with the given [PoisonPolicy]. Unsafe
+        /// for the same reasons as [new()][TaggedCell::new], use [tagged_cell!] for safe
+        /// [TaggedCell] creation
+        #[doc(hidden)]
+        pub unsafe fn with_policy(policy: PoisonPolicy) -> Self {
+            TaggedCell {
+                data: UnsafeCell::new(MaybeUninit::<T>::uninit()),
+                tag: PhantomData,
+                once: Once::new(),
+                policy,
+                #[cfg(feature = "testing")]
+                override_id: testing::OverrideId::new(),
+            }
         }
     }
 
@@ -36,27 +171,258 @@ impl<T, Tag> TaggedCell<T, Tag> {
     ///
     /// Each thread accessing a TaggedOnceCell should call this method to obtain a Tag, the
     /// initialization code will only run once. It is undetermined which thread will run the
-    /// initialization code.
+    /// initialization code. If the initialization code panics, the cell's [PoisonPolicy] decides
+    /// whether it is poisoned, left uninitialized, or the process is aborted.
+    ///
+    /// # Panics
+    /// Panics if the cell has been poisoned, see [try_init()][TaggedCell::try_init] for a
+    /// non-panicking alternative. With the `std` feature, also panics if called from within the
+    /// cell's own initializer, directly or through other cells' initializers, naming the cells
+    /// involved rather than deadlocking. This covers async initializers while they are being
+    /// polled, see [init_async()][TaggedCell::init_async]. Without it, such a call spins forever.
+    ///
+    /// The initializer is only ever called once, so it may move owned values into the cell. If
+    /// the cell is already initialized the initializer is dropped without being called.
     pub fn init<F>(&self, f: F) -> Init<Tag>
     where
-        F: Fn() -> T,
+        F: FnOnce() -> T,
     {
-        unsafe {
-            self.once.call_once(|| {
-                let mut_data = &mut *self.data.get();
-                mut_data.write(f());
-            });
+        match self.try_init(|| Ok::<T, Infallible>(f())) {
+            Ok(tag) => tag,
+            Err(InitError::Poisoned(e)) => panic!("{}", e),
+            Err(InitError::Failed(never)) => match never {},
         }
-        Init { tag: self.tag }
     }
 
-    /// Get the data within a [TaggedCell], requires an tag (obtained via [TaggedCell::init]) to perform the access
-    pub fn get(&self, _: Init<Tag>) -> &T {
+    /// Fallibly initialize a TaggedCell. Behaves like [init()][TaggedCell::init], except that the
+    /// initializer may return an error. On `Err` the cell is left uninitialized, the error is
+    /// handed back to the caller as [InitError::Failed], and a later call to
+    /// [init()][TaggedCell::init] or [try_init()][TaggedCell::try_init] will run its initializer
+    /// again. If the cell has been poisoned, [InitError::Poisoned] is returned instead of panicking.
+    ///
+    /// An [Init] tag is only returned once the cell has been successfully initialized.
+    pub fn try_init<F, E>(&self, f: F) -> Result<Init<Tag>, InitError<E>>
+    where
+        F: FnOnce() -> Result<T, E>,
+    {
+        #[cfg(feature = "testing")]
+        if self.overridden().is_some() {
+            return Ok(Init { tag: PhantomData });
+        }
+        let res = self.once.try_call(self.policy, type_name::<Tag>(), || {
+            let val = f()?;
+            // SAFETY: called from within the running initializer
+            unsafe { self.write(val) };
+            Ok(())
+        });
+        match res {
+            Ok(()) => Ok(Init { tag: PhantomData }),
+            Err(CallError::Failed(e)) => Err(InitError::Failed(e)),
+            Err(CallError::Poisoned) => Err(InitError::Poisoned(PoisonError::new::<Tag>())),
+        }
+    }
+
+    /// Async version of [init()][TaggedCell::init]. Exactly one task runs the future returned by
+    /// `f`, while concurrent callers are suspended until it finishes instead of blocking their
+    /// thread. Works with any executor.
+    ///
+    /// If the initializing future is dropped before completing, the cell is left uninitialized
+    /// and a waiting task takes over initialization with its own initializer. Without the `std`
+    /// feature a panic can't be told apart from the future being dropped, so it also leaves the
+    /// cell uninitialized, and waiting tasks are polled again straight away rather than woken.
+    ///
+    /// # Panics
+    /// Panics if the cell has been poisoned. With the `std` feature, also panics if the cell is
+    /// initialized from within its own async initializer, like [init()][TaggedCell::init].
+    ///
+    /// Only calls made while the initializer is being polled are detected. A blocking
+    /// [init()][TaggedCell::init] from another task, while the initializer is suspended, blocks
+    /// its thread until the initializer finishes, which deadlocks if both tasks share the thread.
+    pub async fn init_async<F, Fut>(&self, f: F) -> Init<Tag>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        #[cfg(feature = "testing")]
+        if self.overridden().is_some() {
+            return Init { tag: PhantomData };
+        }
+        if !self.once.is_completed() {
+            loop {
+                match self.once.begin(self.policy) {
+                    Begin::Acquired(finish) => {
+                        // Each poll runs as the initializer, so waiting on the cell from within
+                        // it is detected
+                        let name = type_name::<Tag>();
+                        let mut fut = pin!(self.once.run_initializer(name, f));
+                        let val = poll_fn(|cx| {
+                            self.once.run_initializer(name, || fut.as_mut().poll(cx))
+                        })
+                        .await;
+                        // SAFETY: `finish` proves this task owns the running initializer
+                        unsafe { self.write(val) };
+                        finish.complete();
+                        break;
+                    }
+                    Begin::Complete => break,
+                    Begin::Poisoned => panic!("{}", PoisonError::new::<Tag>()),
+                    Begin::Running => self.once.wait_async(type_name::<Tag>()).await,
+                }
+            }
+        }
+        Init { tag: PhantomData }
+    }
+
+    /// Initialize a TaggedCell with a ready-made value. If the cell is empty, `value` is stored
+    /// and an [Init] tag is returned. If the cell was already initialized, or has been poisoned,
+    /// `value` is handed back unchanged so double initialization can be detected.
+    ///
+    /// Like [init()][TaggedCell::init], this blocks while another thread is running an
+    /// initializer.
+    pub fn set(&self, value: T) -> Result<Init<Tag>, T> {
+        // An overridden cell behaves as if already initialized with its override
+        #[cfg(feature = "testing")]
+        if self.overridden().is_some() {
+            return Err(value);
+        }
+        let mut value = Some(value);
+        let _ = self.once.try_call(self.policy, type_name::<Tag>(), || {
+            // SAFETY: called from within the running initializer
+            unsafe { self.write(value.take().unwrap()) };
+            Ok::<(), Infallible>(())
+        });
+        match value {
+            None => Ok(Init { tag: PhantomData }),
+            Some(value) => Err(value),
+        }
+    }
+
+    /// Get a mutable reference to the data within a [TaggedCell]. Like [get()][TaggedCell::get]
+    /// this requires a tag to prove the cell is initialized, while `&mut self` proves nothing else
+    /// is accessing it
+    pub fn get_mut<P, I>(&mut self, _: P) -> &mut T
+    where
+        P: Proves<Tag, I>,
+    {
+        #[cfg(feature = "testing")]
+        {
+            self.assert_not_overridden();
+            self.assert_initialized();
+        }
+        // SAFETY: Init tag proves the cell is initialized, see `get`
+        unsafe { self.data.with_mut(|data| (*data).assume_init_mut()) }
+    }
+
+    /// Take the data out of an initialized cell, returning it to the uninitialized state so the
+    /// next [init()][TaggedCell::init] runs again. Returns `None` if the cell was not initialized.
+    /// A poisoned cell is also reset.
+    ///
+    /// # Safety
+    /// Any [Init] tags obtained for this cell before the call no longer prove that it is
+    /// initialized. The caller must ensure none of them are passed to [get()][TaggedCell::get] or
+    /// [get_mut()][TaggedCell::get_mut] afterwards, use a fresh tag from
+    /// [init()][TaggedCell::init] instead.
+    pub unsafe fn take(&mut self) -> Option<T> {
+        let initialized = self.once.is_completed();
+        self.once.reset();
+        if initialized {
+            // SAFETY: the cell was initialized, and is now marked as uninitialized so the value
+            // can't be read or dropped again
+            Some(self.data.with_mut(|data| (*data).assume_init_read()))
+        } else {
+            None
+        }
+    }
+
+    /// Drop the data of an initialized cell, returning it to the uninitialized state so the next
+    /// [init()][TaggedCell::init] runs again. A poisoned cell is also reset. This lets each test
+    /// in a binary initialize a shared static with its own fixture, and requires the `testing`
+    /// feature.
+    ///
+    /// # Safety
+    /// Nothing else may be using the cell during the call: no thread may be running its
+    /// initializer or holding a reference from [get()][TaggedCell::get]. Any [Init] tags obtained
+    /// before the call no longer prove that the cell is initialized, and must not be used
+    /// afterwards. Tests sharing a static must not run concurrently, for example by holding a
+    /// lock for their whole duration.
+    #[cfg(feature = "testing")]
+    pub unsafe fn reset(&self) {
+        let initialized = self.once.is_completed();
+        self.once.reset();
+        if initialized {
+            // SAFETY: the cell was initialized, and is now marked as uninitialized so the value
+            // can't be read or dropped again
+            self.data.with_mut(|data| (*data).assume_init_drop());
+        }
+    }
+
+    /// Consume the cell, returning its data if it was initialized
+    pub fn into_inner(mut self) -> Option<T> {
+        // SAFETY: the cell is consumed, so no tag can be used with it again
+        unsafe { self.take() }
+    }
+
+    /// Returns true if the cell has been successfully initialized. Never blocks, and never runs an
+    /// initializer
+    pub fn is_initialized(&self) -> bool {
+        self.once.is_completed()
+    }
+
+    /// Non-blocking alternative to [init()][TaggedCell::init]. If the cell has already been
+    /// initialized, returns an [Init] tag along with the data. Returns `None` if the cell is
+    /// uninitialized, poisoned, or another thread is still running its initializer
+    pub fn try_get(&self) -> Option<(Init<Tag>, &T)> {
+        #[cfg(feature = "testing")]
+        if let Some(val) = self.overridden() {
+            return Some((Init { tag: PhantomData }, val));
+        }
+        if self.is_initialized() {
+            let tag = Init { tag: PhantomData };
+            Some((tag, self.get(tag)))
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if an initializer panicked and poisoned the cell, see [PoisonPolicy::Poison]
+    pub fn is_poisoned(&self) -> bool {
+        self.once.is_poisoned()
+    }
+
+    /// Write the cell's data
+    ///
+    /// # Safety
+    /// Must only be called by the owner of the running initializer, so nothing else can be
+    /// accessing the data
+    unsafe fn write(&self, val: T) {
+        self.data.with_mut(|data| (*data).write(val));
+    }
+
+    /// Get the data within a [TaggedCell], requires an tag (obtained via [TaggedCell::init]) to perform the access.
+    /// A composite proof containing the cell's tag is also accepted, see [Proves]
+    pub fn get<P, I>(&self, _: P) -> &T
+    where
+        P: Proves<Tag, I>,
+    {
+        #[cfg(feature = "testing")]
+        {
+            if let Some(val) = self.overridden() {
+                return val;
+            }
+            self.assert_initialized();
+        }
         // SAFETY: Init tag proves that `init` has successfully
         // returned before in the current thread, initializing the cell.
-        unsafe {
-            let maybe_val = &mut *self.data.get();
-            maybe_val.assume_init_ref()
+        unsafe { self.data.with(|data| (*data).assume_init_ref()) }
+    }
+}
+
+impl<T, Tag> Drop for TaggedCell<T, Tag> {
+    fn drop(&mut self) {
+        if self.once.is_completed() {
+            // SAFETY: the cell was successfully initialized, and `&mut self` guarantees no
+            // references to the data are still alive
+            unsafe { self.data.with_mut(|data| (*data).assume_init_drop()) }
         }
     }
 }
@@ -67,26 +433,149 @@ unsafe impl<T: Sync + Send, Tag> Sync for TaggedCell<T, Tag> {}
 /// [TaggedCell] may be Sync. Guaranteed by ZST tag
 unsafe impl<T: Send, Tag> Send for TaggedCell<T, Tag> {}
 
-/// Safe macro for creating a [TaggedCell]
+/// Safe macro for creating a [TaggedCell]. With `_` as the tag, each declaration gets a unique tag
+/// type `TagType` in a module sharing the static's name. Naming the tag instead declares a unit
+/// struct with that name, which reads better in function signatures taking an [Init] proof.
+///
+/// Visibility is applied to both the static and its tag, and attributes are forwarded to the
+/// static, with `cfg` attributes also applied to the tag. Several statics may be declared in one
+/// invocation
+/// ```
+/// use tagged_cell::{tagged_cell, Init, PoisonPolicy};
+///
+/// tagged_cell!{
+///     /// Shared with the rest of the crate
+///     pub(crate) static COUNT: TaggedCell<usize, _> = TaggedCell::new();
+///     static NAMES: TaggedCell<Vec<&'static str>, _> = TaggedCell::lazy(|| vec!["a", "b"]);
+///     #[cfg(unix)]
+///     static RETRIED: TaggedCell<u8, _> = TaggedCell::with_policy(PoisonPolicy::Retry);
+///     static PORT: TaggedCell<u16, PortTag> = TaggedCell::new();
+/// }
+///
+/// fn port(tag: Init<PortTag>) -> u16 {
+///     *PORT.get(tag)
+/// }
+///
+/// assert_eq!(*COUNT.get(COUNT.init(|| 2)), NAMES.get(NAMES.init()).len());
+/// assert_eq!(port(PORT.init(|| 80)), 80);
+/// ```
 #[macro_export]
 macro_rules! tagged_cell {
+    () => {};
+    (@static [$($attr:tt)*] $vis:vis $name:ident [$type:ty] [$tag:ty] new ()) => {
+        $($attr)*
+        $vis static $name: $crate::TaggedCell<$type, $tag> =
+            unsafe { $crate::TaggedCell::new() };
+    };
+    (@static [$($attr:tt)*] $vis:vis $name:ident [$type:ty] [$tag:ty] with_policy ($policy:expr)) => {
+        $($attr)*
+        $vis static $name: $crate::TaggedCell<$type, $tag> = {
+            const POLICY: $crate::PoisonPolicy = $policy;
+            unsafe { $crate::TaggedCell::with_policy(POLICY) }
+        };
+    };
+    (@static [$($attr:tt)*] $vis:vis $name:ident [$type:ty] [$tag:ty] lazy ($init:expr)) => {
+        $($attr)*
+        $vis static $name: $crate::TaggedLazy<$type, $tag> = {
+            // Only called here, with the static's unique tag. The data type comes from the
+            // static, as `fn() -> $type` would reject elided lifetimes, and the initializer is
+            // checked against it outside of an unsafe block
+            const fn lazy<T, Tag>(init: fn() -> T) -> $crate::TaggedLazy<T, Tag> {
+                unsafe { $crate::TaggedLazy::new(init) }
+            }
+            lazy($init)
+        };
+    };
+    (@static [$($attr:tt)*] $vis:vis $name:ident [$type:ty] [$tag:ty] requires ($dep:ty)) => {
+        $($attr)*
+        $vis static $name: $crate::TaggedDependent<$type, $tag, $dep> =
+            unsafe { $crate::TaggedDependent::new() };
+    };
+    (@static [$($attr:tt)*] $vis:vis $name:ident [$type:ty] [$tag:ty] requires ($($dep:ty),+)) => {
+        $($attr)*
+        $vis static $name: $crate::TaggedDependent<$type, $tag, ($($dep,)+)> =
+            unsafe { $crate::TaggedDependent::new() };
+    };
+    (@array [$($attr:tt)*] $vis:vis $name:ident [$type:ty] [$tag:ty] [$len:tt]) => {
+        $($attr)*
+        $vis static $name: $crate::TaggedCellArray<$type, $tag, $len> =
+            unsafe { $crate::TaggedCellArray::new() };
+    };
+    (
+        $(#[$($attr:tt)*])*
+        $vis:vis static $name:ident : TaggedCellArray<$type:ty, _, $len:tt> = TaggedCellArray::new();
+        $($rest:tt)*
+    ) => {
+        $crate::__tag_type!([] [$(#[$($attr)*])*] $vis $name);
+        $crate::tagged_cell!(
+            @array [$(#[$($attr)*])*] $vis $name [$type] [$name::TagType] [$len]
+        );
+        $crate::tagged_cell!($($rest)*);
+    };
+    (
+        $(#[$($attr:tt)*])*
+        $vis:vis static $name:ident : TaggedCellArray<$type:ty, $tag:ident, $len:tt> = TaggedCellArray::new();
+        $($rest:tt)*
+    ) => {
+        $crate::__tag_type!([] [$(#[$($attr)*])*] $vis $name $tag);
+        $crate::tagged_cell!(@array [$(#[$($attr)*])*] $vis $name [$type] [$tag] [$len]);
+        $crate::tagged_cell!($($rest)*);
+    };
+    (
+        $(#[$($attr:tt)*])*
+        $vis:vis static $name:ident : TaggedCell<$type:ty, _> = TaggedCell::$ctor:ident $args:tt;
+        $($rest:tt)*
+    ) => {
+        $crate::__tag_type!([] [$(#[$($attr)*])*] $vis $name);
+        $crate::tagged_cell!(
+            @static [$(#[$($attr)*])*] $vis $name [$type] [$name::TagType] $ctor $args
+        );
+        $crate::tagged_cell!($($rest)*);
+    };
     (
-        $(#[$outer:meta])*
-        static $name:ident : TaggedCell<$type:ty, _> = TaggedCell::new();
+        $(#[$($attr:tt)*])*
+        $vis:vis static $name:ident : TaggedCell<$type:ty, $tag:ident> = TaggedCell::$ctor:ident $args:tt;
+        $($rest:tt)*
     ) => {
+        $crate::__tag_type!([] [$(#[$($attr)*])*] $vis $name $tag);
+        $crate::tagged_cell!(@static [$(#[$($attr)*])*] $vis $name [$type] [$tag] $ctor $args);
+        $crate::tagged_cell!($($rest)*);
+    };
+}
+
+/// Internal macro declaring the tag type for a static, keeping only its `cfg` attributes. Without
+/// an explicit tag name, the tag is declared in a module sharing the static's name
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tag_type {
+    ([$($cfg:tt)*] [] $vis:vis $name:ident) => {
+        $($cfg)*
+        #[doc = concat!("Tag module for `", stringify!($name), "`")]
         #[allow(non_snake_case)]
-        mod $name {
+        $vis mod $name {
+            #[doc = concat!("Unique tag type of `", stringify!($name), "`")]
             #[allow(dead_code)]
             pub struct TagType;
         }
-
-        static $name: $crate::TaggedCell<$type, $name::TagType> =
-            unsafe { $crate::TaggedCell::new() };
+    };
+    ([$($cfg:tt)*] [] $vis:vis $name:ident $tag:ident) => {
+        $($cfg)*
+        #[doc = concat!("Unique tag type of `", stringify!($name), "`")]
+        #[allow(dead_code)]
+        $vis struct $tag;
+    };
+    ([$($cfg:tt)*] [#[cfg $($args:tt)*] $($rest:tt)*] $vis:vis $name:ident $($tag:ident)?) => {
+        $crate::__tag_type!([$($cfg)* #[cfg $($args)*]] [$($rest)*] $vis $name $($tag)?);
+    };
+    ([$($cfg:tt)*] [#[$($other:tt)*] $($rest:tt)*] $vis:vis $name:ident $($tag:ident)?) => {
+        $crate::__tag_type!([$($cfg)*] [$($rest)*] $vis $name $($tag)?);
     };
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(loom)))]
 mod tests {
+    use crate::{Init, InitError, SendInit};
+
     #[test]
     fn simple() {
         tagged_cell! {
@@ -99,4 +588,855 @@ mod tests {
 
         assert_eq!(*num, 0);
     }
+
+    #[test]
+    fn try_init_retries_after_error() {
+        tagged_cell! {
+            static TEST: TaggedCell<usize, _> = TaggedCell::new();
+        }
+
+        assert_eq!(
+            TEST.try_init(|| Err("not yet")).err(),
+            Some(InitError::Failed("not yet"))
+        );
+
+        let tag = TEST.try_init(|| Ok::<_, &str>(7)).unwrap();
+        assert_eq!(*TEST.get(tag), 7);
+
+        // once initialized, later initializers never run
+        let tag = TEST.try_init(|| Err("unreachable")).unwrap();
+        assert_eq!(*TEST.get(tag), 7);
+    }
+
+    #[test]
+    fn try_get() {
+        tagged_cell! {
+            static TEST: TaggedCell<usize, _> = TaggedCell::new();
+        }
+
+        assert!(!TEST.is_initialized());
+        assert!(TEST.try_get().is_none());
+
+        TEST.init(|| 11);
+        assert!(TEST.is_initialized());
+
+        let (tag, num) = TEST.try_get().unwrap();
+        assert_eq!(*num, 11);
+        assert_eq!(*TEST.get(tag), 11);
+    }
+
+    #[test]
+    fn set() {
+        tagged_cell! {
+            static TEST: TaggedCell<String, _> = TaggedCell::new();
+        }
+
+        let value = String::from("from main");
+        let tag = TEST.set(value).unwrap();
+        assert_eq!(TEST.get(tag), "from main");
+
+        // a second value is handed back, the first is kept
+        assert_eq!(TEST.set(String::from("again")).err().unwrap(), "again");
+        let tag = TEST.init(String::new);
+        assert_eq!(TEST.get(tag), "from main");
+    }
+
+    #[test]
+    fn init_moves_captures() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::thread;
+
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        struct Counted(usize);
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        tagged_cell! {
+            static TEST: TaggedCell<Vec<Counted>, _> = TaggedCell::new();
+        }
+
+        thread::scope(|s| {
+            for i in 0..8 {
+                // move-only capture, built outside the initializer
+                let vec = vec![Counted(i)];
+                s.spawn(move || {
+                    let tag = TEST.init(move || vec);
+                    assert_eq!(TEST.get(tag).len(), 1);
+                });
+            }
+        });
+
+        // every losing initializer was dropped along with its capture, the winner's lives on
+        assert_eq!(DROPS.load(Ordering::SeqCst), 7);
+        let tag = TEST.init(Vec::new);
+        assert!(TEST.get(tag)[0].0 < 8);
+    }
+
+    mod poison {
+        use crate::{InitError, PoisonPolicy};
+        use std::panic;
+
+        #[test]
+        fn poison() {
+            tagged_cell! {
+                static TEST: TaggedCell<usize, _> = TaggedCell::new();
+            }
+
+            assert!(panic::catch_unwind(|| TEST.init(|| panic!("init failed"))).is_err());
+            assert!(TEST.is_poisoned());
+
+            // try_init reports the poisoned cell by name, without running the initializer
+            match TEST.try_init(|| -> Result<usize, ()> { unreachable!() }) {
+                Err(InitError::Poisoned(e)) => assert!(e.cell().contains("TEST")),
+                _ => panic!("expected a poisoned cell"),
+            }
+
+            let err = panic::catch_unwind(|| TEST.init(|| 0)).err().unwrap();
+            let msg = err.downcast_ref::<String>().unwrap();
+            assert!(msg.contains("TEST") && msg.contains("poisoned"));
+        }
+
+        #[test]
+        fn retry() {
+            tagged_cell! {
+                static TEST: TaggedCell<usize, _> = TaggedCell::with_policy(PoisonPolicy::Retry);
+            }
+
+            assert!(panic::catch_unwind(|| TEST.init(|| panic!("init failed"))).is_err());
+            assert!(!TEST.is_poisoned());
+
+            let tag = TEST.init(|| 3);
+            assert_eq!(*TEST.get(tag), 3);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    mod reentrant {
+        use std::panic;
+
+        fn message(err: Box<dyn std::any::Any + Send>) -> String {
+            err.downcast::<String>().map(|msg| *msg).unwrap()
+        }
+
+        #[test]
+        fn same_cell() {
+            tagged_cell! {
+                static TEST: TaggedCell<usize, _> = TaggedCell::new();
+            }
+
+            let err = panic::catch_unwind(|| TEST.init(|| *TEST.get(TEST.init(|| 1)))).err();
+            let msg = message(err.unwrap());
+            assert!(msg.contains("re-entrant"), "{}", msg);
+            assert!(msg.contains("TEST::TagType -> "), "{}", msg);
+        }
+
+        #[test]
+        fn two_cell_cycle() {
+            tagged_cell! {
+                static FIRST: TaggedCell<usize, FirstTag> = TaggedCell::new();
+                static SECOND: TaggedCell<usize, SecondTag> = TaggedCell::new();
+            }
+
+            fn first() -> usize {
+                *FIRST.get(FIRST.init(|| second() + 1))
+            }
+            fn second() -> usize {
+                *SECOND.get(SECOND.init(|| first() + 1))
+            }
+
+            let msg = message(panic::catch_unwind(first).unwrap_err());
+            let cycle = msg.split(": ").nth(1).unwrap();
+            let names: Vec<_> = cycle.split(" -> ").collect();
+            assert_eq!(names.len(), 3, "{}", msg);
+            assert!(names[0].ends_with("FirstTag"), "{}", msg);
+            assert!(names[1].ends_with("SecondTag"), "{}", msg);
+            assert!(names[2].ends_with("FirstTag"), "{}", msg);
+
+            // the panic unwound through both initializers, poisoning them
+            assert!(FIRST.is_poisoned() && SECOND.is_poisoned());
+        }
+    }
+
+    mod async_init {
+        use std::{
+            future::Future,
+            panic,
+            pin::pin,
+            sync::{
+                atomic::{AtomicBool, AtomicUsize, Ordering},
+                Arc,
+            },
+            task::{Context, Poll, Wake, Waker},
+            thread::{self, Thread},
+        };
+
+        /// Counts how many times a task was woken
+        #[derive(Default)]
+        struct Counter(AtomicUsize);
+        impl Wake for Counter {
+            fn wake(self: Arc<Self>) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        /// Minimal executor, parks the current thread until the future's waker is called
+        pub(super) fn block_on<F: Future>(fut: F) -> F::Output {
+            struct Unpark(Thread);
+            impl Wake for Unpark {
+                fn wake(self: Arc<Self>) {
+                    self.0.unpark();
+                }
+            }
+
+            let waker = Waker::from(Arc::new(Unpark(thread::current())));
+            let mut cx = Context::from_waker(&waker);
+            let mut fut = pin!(fut);
+            loop {
+                match fut.as_mut().poll(&mut cx) {
+                    Poll::Ready(out) => return out,
+                    Poll::Pending => thread::park(),
+                }
+            }
+        }
+
+        #[test]
+        #[cfg(feature = "std")]
+        fn reentrant() {
+            tagged_cell! {
+                static SYNC: TaggedCell<usize, _> = TaggedCell::new();
+                static ASYNC: TaggedCell<usize, _> = TaggedCell::new();
+            }
+
+            let err = panic::catch_unwind(|| {
+                block_on(SYNC.init_async(|| async { *SYNC.get(SYNC.init(|| 1)) + 1 }))
+            });
+            let msg = *err.err().unwrap().downcast::<String>().unwrap();
+            assert!(msg.contains("re-entrant"), "{}", msg);
+            assert!(msg.contains("SYNC::TagType -> "), "{}", msg);
+
+            let err = panic::catch_unwind(|| {
+                block_on(ASYNC.init_async(|| async {
+                    *ASYNC.get(ASYNC.init_async(|| async { 1 }).await) + 1
+                }))
+            });
+            let msg = *err.err().unwrap().downcast::<String>().unwrap();
+            assert!(msg.contains("ASYNC::TagType -> "), "{}", msg);
+        }
+
+        /// Future that stays pending until the gate is opened
+        async fn gate(open: &AtomicBool) {
+            std::future::poll_fn(|_| {
+                if open.load(Ordering::SeqCst) {
+                    Poll::Ready(())
+                } else {
+                    Poll::Pending
+                }
+            })
+            .await
+        }
+
+        #[test]
+        fn waiters_suspend() {
+            tagged_cell! {
+                static TEST: TaggedCell<usize, _> = TaggedCell::new();
+            }
+
+            let open = AtomicBool::new(false);
+            let waker_a = Waker::from(Arc::new(Counter::default()));
+            let counter_b = Arc::new(Counter::default());
+            let waker_b = Waker::from(counter_b.clone());
+
+            let mut a = pin!(TEST.init_async(|| async {
+                gate(&open).await;
+                1
+            }));
+            let mut b = pin!(TEST.init_async(|| async { unreachable!() }));
+
+            // `a` owns the initializer, `b` is suspended rather than blocking this thread
+            assert!(a.as_mut().poll(&mut Context::from_waker(&waker_a)).is_pending());
+            assert!(b.as_mut().poll(&mut Context::from_waker(&waker_b)).is_pending());
+
+            open.store(true, Ordering::SeqCst);
+            let Poll::Ready(tag) = a.as_mut().poll(&mut Context::from_waker(&waker_a)) else {
+                panic!("initializer should have finished");
+            };
+            assert_eq!(*TEST.get(tag), 1);
+
+            // finishing the initializer woke `b`, which now sees the value
+            assert_eq!(counter_b.0.load(Ordering::SeqCst), 1);
+            let Poll::Ready(tag) = b.as_mut().poll(&mut Context::from_waker(&waker_b)) else {
+                panic!("waiter should have finished");
+            };
+            assert_eq!(*TEST.get(tag), 1);
+        }
+
+        #[test]
+        fn cancelled_initializer() {
+            tagged_cell! {
+                static TEST: TaggedCell<usize, _> = TaggedCell::new();
+            }
+
+            let open = AtomicBool::new(false);
+            let waker = Waker::from(Arc::new(Counter::default()));
+            {
+                let mut a = pin!(TEST.init_async(|| async {
+                    gate(&open).await;
+                    1
+                }));
+                assert!(a.as_mut().poll(&mut Context::from_waker(&waker)).is_pending());
+            }
+
+            // dropping the running initializer leaves the cell free for the next one
+            assert!(!TEST.is_initialized() && !TEST.is_poisoned());
+            let tag = block_on(TEST.init_async(|| async { 2 }));
+            assert_eq!(*TEST.get(tag), 2);
+        }
+
+        #[test]
+        fn across_threads() {
+            tagged_cell! {
+                static TEST: TaggedCell<Vec<usize>, _> = TaggedCell::new();
+            }
+
+            thread::scope(|s| {
+                for _ in 0..4 {
+                    s.spawn(|| {
+                        let tag = block_on(TEST.init_async(|| async { vec![0, 10, 20] }));
+                        assert_eq!(TEST.get(tag)[2], 20);
+                    });
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn lazy() {
+        tagged_cell! {
+            static TEST: TaggedCell<Vec<usize>, _> = TaggedCell::lazy(|| vec![1, 2, 3]);
+        }
+
+        assert!(!TEST.is_initialized());
+        let tag = TEST.init();
+        assert_eq!(TEST.get(tag), &[1, 2, 3]);
+
+        let handle = std::thread::spawn(|| TEST.get(TEST.init()).len());
+        assert_eq!(handle.join().unwrap(), 3);
+    }
+
+    #[test]
+    fn lazy_deref() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+        tagged_cell! {
+            static TEST: TaggedCell<String, TestTag> = TaggedCell::lazy(|| {
+                RUNS.fetch_add(1, Ordering::SeqCst);
+                String::from("lazy")
+            });
+        }
+
+        fn unchecked(tag: Init<TestTag>) -> usize {
+            TEST.get(tag).len()
+        }
+
+        assert!(!TEST.is_initialized());
+        // the first deref runs the initializer, later ones and the proof reuse its value
+        assert_eq!(&*TEST, "lazy");
+        assert_eq!(TEST.to_uppercase(), "LAZY");
+        assert_eq!(unchecked(TEST.proof()), 4);
+        assert_eq!(RUNS.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn composite_proof() {
+        use crate::Proves;
+
+        tagged_cell! {
+            static CONFIG: TaggedCell<&'static str, ConfigTag> = TaggedCell::new();
+            static LOGGER: TaggedCell<Vec<String>, LoggerTag> = TaggedCell::new();
+            static METRICS: TaggedCell<usize, MetricsTag> = TaggedCell::new();
+        }
+
+        type Ready = Init<(ConfigTag, LoggerTag, MetricsTag)>;
+
+        fn report(ready: Ready) -> String {
+            // a single token proves every dependency, in any order
+            format!(
+                "{}: {} lines, {} events",
+                CONFIG.get(ready),
+                LOGGER.get(ready).len(),
+                METRICS.get(ready)
+            )
+        }
+
+        let ready: Ready = (
+            CONFIG.init(|| "svc"),
+            LOGGER.init(Vec::new),
+            METRICS.init(|| 3),
+        )
+            .into();
+        assert_eq!(report(ready), "svc: 0 lines, 3 events");
+
+        let metrics: Init<MetricsTag> = ready.proof();
+        assert_eq!(*METRICS.get(metrics), 3);
+        let (config, _, _) = ready.split();
+        assert_eq!(*CONFIG.get(config), "svc");
+    }
+
+    #[test]
+    fn dependent() {
+        tagged_cell! {
+            static CONFIG: TaggedCell<&'static str, ConfigTag> = TaggedCell::new();
+            static LOGGER: TaggedCell<String, LoggerTag> = TaggedCell::requires(ConfigTag);
+            static DB: TaggedCell<String, _> = TaggedCell::requires(ConfigTag, LoggerTag);
+        }
+
+        let config = CONFIG.init(|| "prod");
+        let logger = LOGGER.init_with(config, |config| format!("log-{}", CONFIG.get(config)));
+        let db = DB.init_with((config, logger), |deps| {
+            format!("{} via {}", CONFIG.get(deps), LOGGER.get(deps))
+        });
+
+        assert_eq!(LOGGER.get(logger), "log-prod");
+        assert_eq!(DB.get(db), "prod via log-prod");
+    }
+
+    #[test]
+    fn send_init() {
+        tagged_cell! {
+            static TEST: TaggedCell<usize, _> = TaggedCell::new();
+        }
+
+        let tag = SendInit::from(TEST.init(|| 4));
+        let num = std::thread::spawn(move || *TEST.get(Init::from(tag))).join();
+        assert_eq!(num.unwrap(), 4);
+    }
+
+    #[cfg(feature = "std")]
+    mod thread_local {
+        use crate::tagged_thread_local;
+        use std::{cell::Cell, thread};
+
+        // Each thread's value is leaked by design, which miri reports
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn per_thread() {
+            tagged_thread_local! {
+                static SCRATCH: TaggedThreadLocal<Cell<usize>, _> = TaggedThreadLocal::new();
+            }
+
+            let tag = SCRATCH.init(|| Cell::new(1));
+            SCRATCH.get(tag).set(2);
+
+            thread::spawn(|| {
+                assert!(!SCRATCH.is_initialized());
+                let tag = SCRATCH.init(|| Cell::new(10));
+                assert_eq!(SCRATCH.get(tag).get(), 10);
+            })
+            .join()
+            .unwrap();
+
+            // this thread's value is untouched, and is not re-initialized
+            let tag = SCRATCH.init(|| Cell::new(0));
+            assert_eq!(SCRATCH.get(tag).get(), 2);
+        }
+
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn reentrant_init() {
+            tagged_thread_local! {
+                static TEST: TaggedThreadLocal<usize, _> = TaggedThreadLocal::new();
+            }
+
+            let tag = TEST.init(|| {
+                TEST.init(|| 1);
+                2
+            });
+            assert_eq!(*TEST.get(tag), 1);
+        }
+    }
+
+    mod unsync {
+        use crate::unsync::TaggedCell;
+        use std::panic;
+
+        struct Tag;
+
+        #[test]
+        fn init_once() {
+            let cell = unsafe { TaggedCell::<usize, Tag>::new() };
+            assert!(cell.try_get().is_none());
+            assert_eq!(cell.try_init(|| Err("not yet")).err(), Some("not yet"));
+
+            let tag = cell.init(|| 1);
+            assert_eq!(*cell.get(tag), 1);
+            assert_eq!(cell.set(2).err(), Some(2));
+            assert_eq!(*cell.get(cell.init(|| 3)), 1);
+            assert_eq!(cell.into_inner(), Some(1));
+        }
+
+        #[test]
+        fn panic_leaves_uninitialized() {
+            let cell = unsafe { TaggedCell::<usize, Tag>::new() };
+            let res = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                cell.init(|| panic!("init failed"))
+            }));
+            assert!(res.is_err());
+            assert!(!cell.is_initialized());
+            assert_eq!(*cell.get(cell.init(|| 4)), 4);
+        }
+
+        #[test]
+        fn reentrant_init() {
+            let cell = unsafe { TaggedCell::<String, Tag>::new() };
+            let tag = cell.init(|| {
+                let inner = cell.get(cell.init(|| String::from("inner")));
+                format!("outer after {}", inner)
+            });
+            // the value seen by the inner initializer is kept, so its reference stays valid
+            assert_eq!(cell.get(tag), "inner");
+        }
+    }
+
+    #[cfg(feature = "testing")]
+    mod reset {
+        use std::{
+            cell::Cell,
+            sync::{Mutex, PoisonError},
+        };
+
+        /// Serializes the tests sharing `FIXTURE`, as required by `reset`
+        static LOCK: Mutex<()> = Mutex::new(());
+
+        tagged_cell! {
+            static FIXTURE: TaggedCell<String, _> = TaggedCell::new();
+            static FIXTURE_LAZY: TaggedCell<Vec<usize>, _> = TaggedCell::lazy(|| vec![1, 2]);
+        }
+
+        fn with_fixture(name: &str, f: impl FnOnce(&str)) {
+            let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+            // SAFETY: the lock is held, and no tag outlives this function
+            unsafe { FIXTURE.reset() };
+            f(FIXTURE.get(FIXTURE.init(|| name.to_string())));
+        }
+
+        #[test]
+        fn first_fixture() {
+            with_fixture("first", |val| assert_eq!(val, "first"));
+        }
+
+        #[test]
+        fn second_fixture() {
+            with_fixture("second", |val| assert_eq!(val, "second"));
+        }
+
+        #[test]
+        fn drops_old_value() {
+            use super::drop::{Counted, Tag};
+
+            let drops = Cell::new(0);
+            let cell = unsafe { crate::TaggedCell::<_, Tag>::new() };
+            cell.init(|| Counted(&drops));
+            unsafe { cell.reset() };
+            assert_eq!(drops.get(), 1);
+            assert!(!cell.is_initialized());
+
+            cell.init(|| Counted(&drops));
+            drop(cell);
+            assert_eq!(drops.get(), 2);
+        }
+
+        #[test]
+        fn lazy_reruns_initializer() {
+            let tag = FIXTURE_LAZY.init();
+            assert_eq!(FIXTURE_LAZY.get(tag).len(), 2);
+            // SAFETY: only this test uses the static, and the tag isn't used again
+            unsafe { FIXTURE_LAZY.reset() };
+            assert!(!FIXTURE_LAZY.is_initialized());
+            assert_eq!(FIXTURE_LAZY.get(FIXTURE_LAZY.init()), &[1, 2]);
+        }
+    }
+
+    #[cfg(feature = "testing")]
+    mod override_for_scope {
+        use std::{panic, thread};
+
+        // Override values are leaked by design, which miri reports
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn current_thread_only() {
+            tagged_cell! {
+                static CLOCK: TaggedCell<u64, _> = TaggedCell::new();
+            }
+
+            let guard = CLOCK.override_for_scope(5);
+            // the real initializer doesn't run while overridden
+            let tag = CLOCK.init(|| unreachable!());
+            assert_eq!(*CLOCK.get(tag), 5);
+            assert!(!CLOCK.is_initialized());
+
+            thread::spawn(|| assert_eq!(*CLOCK.get(CLOCK.init(|| 100)), 100))
+                .join()
+                .unwrap();
+
+            {
+                let _inner = CLOCK.override_for_scope(6);
+                assert_eq!(*CLOCK.get(tag), 6);
+            }
+            assert_eq!(CLOCK.try_get().map(|(_, val)| *val), Some(5));
+            drop(guard);
+            assert_eq!(*CLOCK.get(CLOCK.init(|| 100)), 100);
+        }
+
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn stale_tag_panics() {
+            tagged_cell! {
+                static CLOCK: TaggedCell<u64, _> = TaggedCell::new();
+            }
+
+            let tag = {
+                let _guard = CLOCK.override_for_scope(5);
+                CLOCK.init(|| 100)
+            };
+            let err = panic::catch_unwind(|| *CLOCK.get(tag)).err().unwrap();
+            let msg = err.downcast_ref::<String>().unwrap();
+            assert!(msg.contains("CLOCK"), "{}", msg);
+        }
+
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn set_and_init_async() {
+            tagged_cell! {
+                static CLOCK: TaggedCell<u64, _> = TaggedCell::new();
+            }
+
+            {
+                let _guard = CLOCK.override_for_scope(5);
+                // the cell behaves as if already initialized with the override
+                assert_eq!(CLOCK.set(100).err(), Some(100));
+                let tag =
+                    super::async_init::block_on(CLOCK.init_async(|| async { unreachable!() }));
+                assert_eq!(*CLOCK.get(tag), 5);
+                assert!(!CLOCK.is_initialized());
+            }
+            assert!(CLOCK.set(100).is_ok());
+            assert_eq!(*CLOCK.get(CLOCK.init(|| unreachable!())), 100);
+        }
+
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn get_mut_while_overridden() {
+            struct Tag;
+
+            let mut cell = unsafe { crate::TaggedCell::<u64, Tag>::new() };
+            let tag = cell.init(|| 1);
+            // the guard borrows the cell, so only a forgotten guard lets `get_mut` be called
+            std::mem::forget(cell.override_for_scope(5));
+            let err = panic::catch_unwind(panic::AssertUnwindSafe(|| *cell.get_mut(tag)));
+            let msg = *err.unwrap_err().downcast::<String>().unwrap();
+            assert!(msg.contains("while it is overridden"), "{}", msg);
+        }
+
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn forgotten_guard_at_reused_address() {
+            struct Tag;
+            struct OtherTag;
+
+            let cell = Box::new(unsafe { crate::TaggedCell::<u64, Tag>::new() });
+            std::mem::forget(cell.override_for_scope(5));
+            let addr = &*cell as *const _ as usize;
+            drop(cell);
+
+            // same layout, so likely allocated where the first cell was, and must not see its
+            // leaked override
+            let other = Box::new(unsafe { crate::TaggedCell::<f64, OtherTag>::new() });
+            let reused = &*other as *const _ as usize == addr;
+            let tag = other.init(|| 1.5);
+            assert!(other.is_initialized(), "reused address: {}", reused);
+            assert_eq!(*other.get(tag), 1.5);
+        }
+    }
+
+    mod array {
+        use crate::{Init, Slot};
+        use std::{panic, thread};
+
+        #[test]
+        fn runtime_slots() {
+            tagged_cell! {
+                static SHARDS: TaggedCellArray<usize, _, 4> = TaggedCellArray::new();
+            }
+
+            assert_eq!(SHARDS.len(), 4);
+            thread::scope(|s| {
+                for i in 0..8 {
+                    s.spawn(move || {
+                        let tag = SHARDS.init(i % 4, || i % 4 * 10);
+                        assert_eq!(*SHARDS.get(tag), tag.index() * 10);
+                    });
+                }
+            });
+
+            let (tag, val) = SHARDS.try_get(3).unwrap();
+            assert_eq!((tag.index(), *val), (3, 30));
+            assert!(SHARDS.try_get(4).is_none() && !SHARDS.is_initialized(4));
+            assert!(panic::catch_unwind(|| SHARDS.init(4, || 0)).is_err());
+        }
+
+        #[test]
+        fn independent_slots() {
+            tagged_cell! {
+                static DEVICES: TaggedCellArray<String, _, 2> = TaggedCellArray::new();
+            }
+
+            assert!(DEVICES.try_init(0, || Err("unplugged")).is_err());
+            let second = DEVICES.init(1, || String::from("eth1"));
+            assert!(!DEVICES.is_initialized(0));
+            assert_eq!(DEVICES.get(second), "eth1");
+        }
+
+        #[test]
+        fn compile_time_slots() {
+            tagged_cell! {
+                static PORTS: TaggedCellArray<u16, PortsTag, 3> = TaggedCellArray::new();
+            }
+
+            type Both = Init<(Slot<PortsTag, 0>, Slot<PortsTag, 2>)>;
+
+            fn sum(ports: Both) -> u16 {
+                PORTS.get_at::<0, _, _>(ports) + PORTS.get_at::<2, _, _>(ports)
+            }
+
+            let first = PORTS.init_at::<0, _>(|| 80);
+            let last = PORTS.init_at::<2, _>(|| 443);
+            assert_eq!(sum((first, last).into()), 523);
+
+            // a compile time proof also works as a runtime one
+            let last = crate::IndexInit::from(last);
+            assert_eq!((last.index(), *PORTS.get(last)), (2, 443));
+            assert!(!PORTS.is_initialized(1));
+        }
+
+        #[test]
+        #[cfg(feature = "testing")]
+        #[cfg_attr(miri, ignore)]
+        fn reset_and_override() {
+            tagged_cell! {
+                static SHARDS: TaggedCellArray<usize, ShardsTag, 2> = TaggedCellArray::new();
+            }
+
+            let first = SHARDS.init(0, || 1);
+            {
+                let _guard = SHARDS.override_for_scope(1, 20);
+                let second = SHARDS.init(1, || unreachable!());
+                assert_eq!((*SHARDS.get(first), *SHARDS.get(second)), (1, 20));
+                let at = SHARDS.init_at::<1, _>(|| unreachable!());
+                assert_eq!(*SHARDS.get_at::<1, _, _>(at), 20);
+            }
+            assert!(!SHARDS.is_initialized(1));
+
+            // SAFETY: only this test uses the static, and its tags aren't used again
+            unsafe { SHARDS.reset() };
+            assert!(!SHARDS.is_initialized(0));
+            assert_eq!(*SHARDS.get(SHARDS.init(0, || 2)), 2);
+        }
+    }
+
+    mod drop {
+        use crate::TaggedCell;
+        use std::{cell::Cell, panic};
+
+        pub(super) struct Tag;
+
+        /// Counts its drops in the referenced counter
+        pub(super) struct Counted<'a>(pub(super) &'a Cell<usize>);
+        impl Drop for Counted<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        #[test]
+        fn initialized() {
+            let drops = Cell::new(0);
+            let cell = unsafe { TaggedCell::<_, Tag>::new() };
+            cell.init(|| Counted(&drops));
+            // later initializers don't replace, or drop, the value
+            cell.init(|| Counted(&drops));
+            assert_eq!(drops.get(), 0);
+
+            std::mem::drop(cell);
+            assert_eq!(drops.get(), 1);
+        }
+
+        #[test]
+        fn uninitialized() {
+            let drops = Cell::new(0);
+            let cell = unsafe { TaggedCell::<Counted, Tag>::new() };
+            assert!(cell.try_init(|| Err(())).is_err());
+
+            std::mem::drop(cell);
+            assert_eq!(drops.get(), 0);
+        }
+
+        #[test]
+        fn panicked_during_init() {
+            let drops = Cell::new(0);
+            let cell = unsafe { TaggedCell::<Counted, Tag>::new() };
+            let res = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                let captured = Counted(&drops);
+                cell.init(move || {
+                    let _captured = captured;
+                    panic!("init failed")
+                })
+            }));
+            assert!(res.is_err());
+            // only the initializer's capture was dropped, there's no value in the cell
+            assert_eq!(drops.get(), 1);
+
+            std::mem::drop(cell);
+            assert_eq!(drops.get(), 1);
+        }
+
+        #[test]
+        fn take_and_into_inner() {
+            let drops = Cell::new(0);
+            let mut cell = unsafe { TaggedCell::<_, Tag>::new() };
+            assert!(unsafe { cell.take() }.is_none());
+
+            let tag = cell.init(|| vec![Counted(&drops)]);
+            cell.get_mut(tag).push(Counted(&drops));
+
+            let taken = unsafe { cell.take() }.unwrap();
+            assert_eq!(taken.len(), 2);
+            assert!(!cell.is_initialized());
+            std::mem::drop(taken);
+            assert_eq!(drops.get(), 2);
+
+            // the cell can be initialized again, and the new value is moved out by into_inner
+            cell.init(|| vec![Counted(&drops)]);
+            let inner = cell.into_inner().unwrap();
+            assert_eq!(drops.get(), 2);
+            std::mem::drop(inner);
+            assert_eq!(drops.get(), 3);
+        }
+
+        #[test]
+        fn boxed() {
+            let drops = Cell::new(0);
+            let cell = Box::new(unsafe { TaggedCell::<_, Tag>::new() });
+            let tag = cell.init(|| vec![Counted(&drops), Counted(&drops)]);
+            assert_eq!(cell.get(tag).len(), 2);
+
+            std::mem::drop(cell);
+            assert_eq!(drops.get(), 2);
+        }
+    }
 }
@@ -0,0 +1,170 @@
+//! `std` backend, blocking waiting threads on a condvar
+use super::{Once, INCOMPLETE, RUNNING};
+use crate::sync::{AtomicU8, Condvar, Mutex};
+use std::{
+    cell::Cell,
+    ptr,
+    sync::{atomic::Ordering, PoisonError},
+    task::{Context, Poll, Waker},
+};
+
+#[cfg(not(loom))]
+thread_local! {
+    /// Innermost initializer running on this thread, null if there is none
+    static INITIALIZING: Cell<*const Running> = const { Cell::new(ptr::null()) };
+}
+
+// loom runs every modelled thread on the same OS thread, so needs its own thread locals
+#[cfg(loom)]
+loom::thread_local! {
+    static INITIALIZING: Cell<*const Running> = Cell::new(ptr::null());
+}
+
+/// Set along with [RUNNING] once a thread or task is waiting on the initializer
+const WAITING: u8 = 4;
+
+/// Number of times a waiting thread checks the state before blocking
+#[cfg(not(loom))]
+const SPINS: u32 = 100;
+
+pub(super) struct Backend {
+    /// Wakers of async tasks waiting on a running initializer
+    wakers: Mutex<Vec<Waker>>,
+    cvar: Condvar,
+}
+
+impl Backend {
+    loom_const_fn! {
+        pub(super) fn new() -> Self {
+            Backend {
+                wakers: Mutex::new(Vec::new()),
+                cvar: Condvar::new(),
+            }
+        }
+    }
+
+    /// Move `state` from incomplete to running, or return the state it was found in
+    pub(super) fn begin(&self, state: &AtomicU8) -> Result<(), u8> {
+        state
+            .compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire)
+            .map(drop)
+    }
+
+    /// Block until the running initializer has finished, successfully or not. Most initializers
+    /// are short, so the state is polled for a little while before blocking on the condvar
+    pub(super) fn wait(&self, state: &AtomicU8) {
+        // loom would explore every iteration, and spinning doesn't change what can happen
+        #[cfg(not(loom))]
+        for _ in 0..SPINS {
+            if state.load(Ordering::Acquire) & !WAITING != RUNNING {
+                return;
+            }
+            std::hint::spin_loop();
+        }
+        let mut guard = self.wakers.lock().unwrap_or_else(PoisonError::into_inner);
+        while mark_waiting(state) {
+            guard = self
+                .cvar
+                .wait(guard)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Ready once the running initializer has finished, otherwise registers the task's waker
+    pub(super) fn poll_wait(&self, state: &AtomicU8, cx: &mut Context<'_>) -> Poll<()> {
+        let mut wakers = self.wakers.lock().unwrap_or_else(PoisonError::into_inner);
+        if !mark_waiting(state) {
+            return Poll::Ready(());
+        }
+        if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+
+    /// Store the final state of the initializer, and wake everything waiting on it. The lock is
+    /// only taken if a waiter marked the state, so an uncontended initializer never touches it
+    pub(super) fn publish(&self, state: &AtomicU8, new: u8) {
+        if state.swap(new, Ordering::AcqRel) & WAITING == 0 {
+            return;
+        }
+        let wakers = {
+            // Waiters mark the state under the lock and hold it until they sleep on the condvar,
+            // so once it is taken here none of them can miss the notification
+            let mut wakers = self.wakers.lock().unwrap_or_else(PoisonError::into_inner);
+            std::mem::take(&mut *wakers)
+        };
+        self.cvar.notify_all();
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+/// Set the [WAITING] flag if an initializer is still running, returning false if it has
+/// finished. Must be called with the lock held, see [Backend::publish]
+fn mark_waiting(state: &AtomicU8) -> bool {
+    match state.compare_exchange(
+        RUNNING,
+        RUNNING | WAITING,
+        Ordering::Acquire,
+        Ordering::Acquire,
+    ) {
+        Ok(_) => true,
+        Err(current) => current == RUNNING | WAITING,
+    }
+}
+
+/// If this thread is running the initializer of `once`, describe the chain of initializers
+/// leading back to it
+pub(super) fn cycle(once: &Once, name: &'static str) -> Option<String> {
+    let mut names = Vec::new();
+    let mut running = INITIALIZING.with(Cell::get);
+    // SAFETY: every initializer on the stack is alive, see `Running::enter`
+    while let Some(current) = unsafe { running.as_ref() } {
+        names.push(current.name);
+        if ptr::eq(current.once, once) {
+            names.reverse();
+            names.push(name);
+            return Some(names.join(" -> "));
+        }
+        running = current.outer.get();
+    }
+    None
+}
+
+/// An initializer on this thread's stack of running initializers. The stack is linked through
+/// the callers' frames, so keeping track of it never allocates
+pub(super) struct Running {
+    once: *const Once,
+    name: &'static str,
+    /// Initializer this one is running within, set by [Running::enter]
+    outer: Cell<*const Running>,
+}
+
+impl Running {
+    pub(super) fn new(once: &Once, name: &'static str) -> Self {
+        Running {
+            once,
+            name,
+            outer: Cell::new(ptr::null()),
+        }
+    }
+
+    /// Push this initializer onto the stack, until the returned guard is dropped
+    pub(super) fn enter(&self) -> Entered<'_> {
+        // The guard borrows `self`, and guards are dropped in the reverse order they were
+        // created in, so the stack only ever points to live initializers
+        self.outer.set(INITIALIZING.with(|stack| stack.replace(self)));
+        Entered { running: self }
+    }
+}
+
+/// Pops a [Running] initializer off this thread's stack when dropped
+pub(super) struct Entered<'a> {
+    running: &'a Running,
+}
+
+impl Drop for Entered<'_> {
+    fn drop(&mut self) {
+        INITIALIZING.with(|stack| stack.set(self.running.outer.get()));
+    }
+}
@@ -0,0 +1,49 @@
+//! `no_std` backend for targets without compare and swap atomics, the state is only changed
+//! inside a critical section provided through the `critical-section` crate. Waiters spin
+use super::{INCOMPLETE, RUNNING};
+use core::{
+    hint,
+    sync::atomic::{AtomicU8, Ordering},
+    task::{Context, Poll},
+};
+
+pub(super) struct Backend;
+
+impl Backend {
+    pub(super) const fn new() -> Self {
+        Backend
+    }
+
+    /// Move `state` from incomplete to running, or return the state it was found in
+    pub(super) fn begin(&self, state: &AtomicU8) -> Result<(), u8> {
+        ::critical_section::with(|_| match state.load(Ordering::Acquire) {
+            INCOMPLETE => {
+                state.store(RUNNING, Ordering::Relaxed);
+                Ok(())
+            }
+            other => Err(other),
+        })
+    }
+
+    /// Spin until the running initializer has finished, successfully or not
+    pub(super) fn wait(&self, state: &AtomicU8) {
+        while state.load(Ordering::Acquire) == RUNNING {
+            hint::spin_loop();
+        }
+    }
+
+    /// Ready once the running initializer has finished. There's nowhere to keep wakers, so the
+    /// task asks to be polled again straight away
+    pub(super) fn poll_wait(&self, state: &AtomicU8, cx: &mut Context<'_>) -> Poll<()> {
+        if state.load(Ordering::Acquire) != RUNNING {
+            return Poll::Ready(());
+        }
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+
+    /// Store the final state of the initializer, spinning waiters pick it up on their own
+    pub(super) fn publish(&self, state: &AtomicU8, new: u8) {
+        state.store(new, Ordering::Release);
+    }
+}
@@ -0,0 +1,285 @@
+//! Internal state machine used to run a [TaggedCell][crate::TaggedCell]'s initializer once.
+//!
+//! Unlike `std::sync::Once`, an initializer run through this type may fail, in which case the
+//! state is reset and a later call is free to try again. What happens when an initializer panics
+//! is decided by a [PoisonPolicy].
+//!
+//! The state itself is a single atomic, while starting an initializer, waiting for one to finish
+//! and publishing its result is left to a backend picked by cargo features:
+//! - `std` (default): threads waiting on a running initializer spin briefly, then block on a
+//!   condvar, while async tasks register a [Waker][core::task::Waker] and are woken once the
+//!   initializer finishes. The lock is only taken when something is waiting.
+//!   Each thread keeps a stack of the initializers it is running, so an initializer waiting on
+//!   itself, directly or through a cycle of cells, panics instead of deadlocking.
+//! - `critical-section`, without `std`: the state is only changed inside a critical section, for
+//!   targets whose atomics can't compare and swap. Waiters spin.
+//! - Otherwise, the state is changed with compare and swap, and waiters spin.
+use crate::{sync::AtomicU8, PoisonPolicy};
+use core::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::Ordering,
+    task::{Context, Poll},
+};
+
+#[cfg(feature = "std")]
+mod blocking;
+#[cfg(all(not(feature = "std"), feature = "critical-section"))]
+mod critical;
+#[cfg(not(any(feature = "std", feature = "critical-section")))]
+mod spin;
+
+#[cfg(not(any(feature = "std", feature = "critical-section", target_has_atomic = "8")))]
+compile_error!(
+    "this target has no compare and swap atomics, enable the `critical-section` feature"
+);
+
+#[cfg(feature = "std")]
+use blocking::Backend;
+#[cfg(all(not(feature = "std"), feature = "critical-section"))]
+use critical::Backend;
+#[cfg(not(any(feature = "std", feature = "critical-section")))]
+use spin::Backend;
+
+/// No initializer has completed, and none is currently running
+const INCOMPLETE: u8 = 0;
+/// An initializer is currently running on some thread
+const RUNNING: u8 = 1;
+/// An initializer has completed successfully, the cell's data is written
+const COMPLETE: u8 = 2;
+/// An initializer panicked under [PoisonPolicy::Poison], no initializer will ever run again
+const POISONED: u8 = 3;
+
+/// Reasons [Once::try_call] did not complete
+pub(crate) enum CallError<E> {
+    /// The initializer returned an error
+    Failed(E),
+    /// The state was poisoned by a previous initializer, nothing was run
+    Poisoned,
+}
+
+/// Result of trying to start an initializer with [Once::begin]
+pub(crate) enum Begin<'a> {
+    /// An initializer has already completed
+    Complete,
+    /// The state was poisoned by a previous initializer
+    Poisoned,
+    /// Another initializer is currently running
+    Running,
+    /// The caller now owns the running initializer, and must report how it finished through
+    /// the guard
+    Acquired(Finish<'a>),
+}
+
+pub(crate) struct Once {
+    state: AtomicU8,
+    backend: Backend,
+}
+
+impl Once {
+    loom_const_fn! {
+        pub(crate) fn new() -> Self {
+            Once {
+                state: AtomicU8::new(INCOMPLETE),
+                backend: Backend::new(),
+            }
+        }
+    }
+
+    /// Returns true if an initializer has completed successfully
+    #[inline]
+    pub(crate) fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+
+    /// Returns true if an initializer panicked under [PoisonPolicy::Poison]
+    #[inline]
+    pub(crate) fn is_poisoned(&self) -> bool {
+        self.state.load(Ordering::Acquire) == POISONED
+    }
+
+    /// Return to the initial state, as if no initializer had ever run. Callers must ensure no
+    /// initializer is running, and that nothing relies on a previous initializer having completed
+    pub(crate) fn reset(&self) {
+        self.state.store(INCOMPLETE, Ordering::Relaxed);
+    }
+
+    /// Run `f` if no initializer has completed yet, blocking while another thread is running one.
+    /// The state is only marked complete if `f` returns `Ok`, otherwise the error is passed back
+    /// and the next call will run its own initializer. If `f` panics, `policy` decides the state
+    /// left behind.
+    ///
+    /// # Panics
+    /// With the `std` feature, panics if `f` is already running on the current thread, reporting
+    /// the cycle of initializers by `name`
+    #[inline]
+    pub(crate) fn try_call<F, E>(
+        &self,
+        policy: PoisonPolicy,
+        name: &'static str,
+        f: F,
+    ) -> Result<(), CallError<E>>
+    where
+        F: FnOnce() -> Result<(), E>,
+    {
+        if self.is_completed() {
+            return Ok(());
+        }
+        self.try_call_slow(policy, name, f)
+    }
+
+    #[cold]
+    fn try_call_slow<F, E>(
+        &self,
+        policy: PoisonPolicy,
+        name: &'static str,
+        f: F,
+    ) -> Result<(), CallError<E>>
+    where
+        F: FnOnce() -> Result<(), E>,
+    {
+        loop {
+            match self.begin(policy) {
+                Begin::Acquired(mut finish) => {
+                    // `f` runs to completion or unwinds, it can't be cancelled
+                    finish.cancellable = false;
+                    // If `f` panics the guard applies the policy, so waiters are not left hanging
+                    let res = self.run_initializer(name, f);
+                    match res {
+                        Ok(()) => finish.complete(),
+                        Err(_) => finish.fail(),
+                    }
+                    return res.map_err(CallError::Failed);
+                }
+                Begin::Complete => return Ok(()),
+                Begin::Poisoned => return Err(CallError::Poisoned),
+                Begin::Running => {
+                    self.check_reentrant(name);
+                    self.backend.wait(&self.state)
+                }
+            }
+        }
+    }
+
+    /// Run `f` as part of the initializer owned by the caller. With the `std` feature, waiting on
+    /// this [Once] from within `f` then panics rather than deadlocking, see
+    /// [check_reentrant()][Once::check_reentrant]. Async initializers run each poll through it
+    pub(crate) fn run_initializer<R>(&self, name: &'static str, f: impl FnOnce() -> R) -> R {
+        #[cfg(feature = "std")]
+        let running = blocking::Running::new(self, name);
+        #[cfg(feature = "std")]
+        let _entered = running.enter();
+        #[cfg(not(feature = "std"))]
+        let _ = name;
+        f()
+    }
+
+    /// With the `std` feature, panic if the current thread is inside this [Once]'s initializer,
+    /// naming the chain of initializers leading back to it
+    fn check_reentrant(&self, name: &'static str) {
+        #[cfg(feature = "std")]
+        if let Some(cycle) = blocking::cycle(self, name) {
+            panic!("re-entrant initialization of TaggedCell: {}", cycle);
+        }
+        #[cfg(not(feature = "std"))]
+        let _ = name;
+    }
+
+    /// Try to take ownership of running the initializer. Never blocks
+    pub(crate) fn begin(&self, policy: PoisonPolicy) -> Begin<'_> {
+        match self.backend.begin(&self.state) {
+            Ok(()) => Begin::Acquired(Finish {
+                once: self,
+                policy,
+                state: None,
+                cancellable: true,
+            }),
+            Err(COMPLETE) => Begin::Complete,
+            Err(POISONED) => Begin::Poisoned,
+            Err(_) => Begin::Running,
+        }
+    }
+
+    /// Async version of waiting for the running initializer to finish, suspends the task instead
+    /// of blocking the thread
+    ///
+    /// # Panics
+    /// With the `std` feature, panics if called from within the running initializer, reporting
+    /// the cycle of initializers by `name`
+    pub(crate) fn wait_async(&self, name: &'static str) -> Wait<'_> {
+        self.check_reentrant(name);
+        Wait { once: self }
+    }
+}
+
+/// Future returned by [Once::wait_async]
+pub(crate) struct Wait<'a> {
+    once: &'a Once,
+}
+
+impl Future for Wait<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.once.backend.poll_wait(&self.once.state, cx)
+    }
+}
+
+/// Publishes the final state of a running initializer and wakes up any waiting threads and tasks
+pub(crate) struct Finish<'a> {
+    once: &'a Once,
+    policy: PoisonPolicy,
+    /// State to publish once the initializer returns, `None` if it didn't run to completion
+    state: Option<u8>,
+    /// Whether the initializer may be dropped without finishing, as an async initializer can
+    cancellable: bool,
+}
+
+impl Finish<'_> {
+    /// Mark the initializer as successfully completed
+    pub(crate) fn complete(mut self) {
+        self.state = Some(COMPLETE);
+    }
+
+    /// Mark the initializer as failed, leaving the state ready for another attempt
+    pub(crate) fn fail(mut self) {
+        self.state = Some(INCOMPLETE);
+    }
+}
+
+impl Drop for Finish<'_> {
+    fn drop(&mut self) {
+        let state = match (self.state, self.policy) {
+            (Some(state), _) => state,
+            // An async initializer was dropped before completing
+            (None, _) if self.cancellable && !panicking() => INCOMPLETE,
+            (None, PoisonPolicy::Poison) => POISONED,
+            (None, PoisonPolicy::Retry) => INCOMPLETE,
+            (None, PoisonPolicy::Abort) => abort(),
+        };
+        self.once.backend.publish(&self.once.state, state);
+    }
+}
+
+#[cfg(feature = "std")]
+fn panicking() -> bool {
+    std::thread::panicking()
+}
+
+/// Without `std` a panic can't be detected, so a dropped async initializer is always treated as
+/// cancelled
+#[cfg(not(feature = "std"))]
+fn panicking() -> bool {
+    false
+}
+
+#[cfg(feature = "std")]
+fn abort() -> ! {
+    std::process::abort()
+}
+
+/// Only called while unwinding from a panicking initializer, where panicking again aborts
+#[cfg(not(feature = "std"))]
+fn abort() -> ! {
+    panic!("TaggedCell initializer panicked under PoisonPolicy::Abort")
+}
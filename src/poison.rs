@@ -0,0 +1,76 @@
+//! Policies and errors for [TaggedCell][crate::TaggedCell] initializers that panic
+use core::{error::Error, fmt};
+
+/// What a [TaggedCell][crate::TaggedCell] does when its initializer panics.
+/// Set with [TaggedCell::with_policy][crate::TaggedCell::with_policy], or through the
+/// [tagged_cell!][crate::tagged_cell] macro. Defaults to [PoisonPolicy::Poison]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoisonPolicy {
+    /// Mark the cell as poisoned. Every later [init()][crate::TaggedCell::init] panics with a
+    /// message naming the cell, and [try_init()][crate::TaggedCell::try_init] returns
+    /// [InitError::Poisoned]. The cell can never be initialized
+    Poison,
+    /// Leave the cell uninitialized, so the next initialization attempt runs its initializer
+    Retry,
+    /// Abort the process, so no code can observe the half-finished initialization
+    Abort,
+}
+
+/// The cell with tag named by [cell()][PoisonError::cell] was poisoned by a panicking initializer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoisonError {
+    cell: &'static str,
+}
+
+impl PoisonError {
+    pub(crate) fn new<Tag>() -> Self {
+        PoisonError {
+            cell: core::any::type_name::<Tag>(),
+        }
+    }
+
+    /// The type name of the poisoned cell's tag. For cells made by [tagged_cell!][crate::tagged_cell]
+    /// this includes the name of the static
+    pub fn cell(&self) -> &'static str {
+        self.cell
+    }
+}
+
+impl fmt::Display for PoisonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "TaggedCell `{}` was poisoned by a panic during initialization",
+            self.cell
+        )
+    }
+}
+
+impl Error for PoisonError {}
+
+/// Error returned by [try_init()][crate::TaggedCell::try_init]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InitError<E> {
+    /// The initializer returned an error, the cell was left uninitialized
+    Failed(E),
+    /// A previous initializer panicked and poisoned the cell, no initializer was run
+    Poisoned(PoisonError),
+}
+
+impl<E: fmt::Display> fmt::Display for InitError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InitError::Failed(e) => write!(f, "TaggedCell initialization failed: {}", e),
+            InitError::Poisoned(e) => e.fmt(f),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for InitError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            InitError::Failed(e) => Some(e),
+            InitError::Poisoned(e) => Some(e),
+        }
+    }
+}
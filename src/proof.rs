@@ -0,0 +1,68 @@
+//! Composite [Init] proofs, bundling the proofs of several cells into one token
+use crate::Init;
+use core::marker::PhantomData;
+
+/// Implemented by proofs that the cell with tag `Tag` is initialized. This is an [Init] for that
+/// tag, or a composite [Init] of a tuple of tags containing it. A tuple of [Init] proofs also
+/// proves the matching tuple of tags. `I` is an index worked out by the
+/// compiler, so callers never name it.
+///
+/// This trait is sealed, as implementing it for any other type would allow forging proofs.
+pub trait Proves<Tag, I>: sealed::Sealed {
+    /// Project out the proof for the single cell with tag `Tag`
+    fn proof(&self) -> Init<Tag> {
+        Init { tag: PhantomData }
+    }
+}
+
+/// Index of an [Init] proving its own tag
+pub struct Here;
+
+/// Index of the tag at position `N` of a composite [Init]
+pub struct Elem<const N: usize>;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+impl<Tag> sealed::Sealed for Init<Tag> {}
+
+impl<Tag> Proves<Tag, Here> for Init<Tag> {}
+
+macro_rules! tuple_proofs {
+    ($($idx:tt => $tag:ident),+) => {
+        impl<$($tag),+> Init<($($tag,)+)> {
+            /// Split a composite proof into the proofs of each cell
+            pub fn split(self) -> ($(Init<$tag>,)+) {
+                ($(Init::<$tag> { tag: PhantomData },)+)
+            }
+        }
+
+        impl<$($tag),+> From<($(Init<$tag>,)+)> for Init<($($tag,)+)> {
+            /// Combine the proofs of several cells into a composite proof
+            fn from(_: ($(Init<$tag>,)+)) -> Self {
+                Init { tag: PhantomData }
+            }
+        }
+
+        impl<$($tag),+> sealed::Sealed for ($(Init<$tag>,)+) {}
+
+        impl<$($tag),+> Proves<($($tag,)+), Here> for ($(Init<$tag>,)+) {}
+
+        tuple_proofs!(@elems [$($tag),+] $($idx => $tag),+);
+    };
+    (@elems $all:tt $($idx:tt => $tag:ident),+) => {
+        $(tuple_proofs!(@elem $all $idx => $tag);)+
+    };
+    (@elem [$($all:ident),+] $idx:tt => $tag:ident) => {
+        impl<$($all),+> Proves<$tag, Elem<$idx>> for Init<($($all,)+)> {}
+    };
+}
+
+tuple_proofs!(0 => A, 1 => B);
+tuple_proofs!(0 => A, 1 => B, 2 => C);
+tuple_proofs!(0 => A, 1 => B, 2 => C, 3 => D);
+tuple_proofs!(0 => A, 1 => B, 2 => C, 3 => D, 4 => E);
+tuple_proofs!(0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F);
+tuple_proofs!(0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F, 6 => G);
+tuple_proofs!(0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F, 6 => G, 7 => H);
@@ -0,0 +1,58 @@
+//! Primitives shared between threads by [TaggedCell][crate::TaggedCell]. Building with
+//! `--cfg loom` swaps them for their [loom](https://docs.rs/loom) equivalents, so the model tests
+//! in `tests/loom.rs` can check every interleaving of concurrent initializers and readers
+#[cfg(loom)]
+pub(crate) use loom::{
+    cell::UnsafeCell,
+    sync::{atomic::AtomicU8, Condvar, Mutex},
+};
+
+#[cfg(all(not(loom), feature = "std"))]
+pub(crate) use std::sync::{Condvar, Mutex};
+
+#[cfg(not(loom))]
+pub(crate) use core::sync::atomic::AtomicU8;
+
+/// [core::cell::UnsafeCell] with the closure based API of loom's `UnsafeCell`, which tracks every
+/// access made through it
+#[cfg(not(loom))]
+pub(crate) struct UnsafeCell<T>(core::cell::UnsafeCell<T>);
+
+#[cfg(not(loom))]
+impl<T> UnsafeCell<T> {
+    pub(crate) const fn new(data: T) -> Self {
+        UnsafeCell(core::cell::UnsafeCell::new(data))
+    }
+
+    #[inline]
+    pub(crate) fn with<R>(&self, f: impl FnOnce(*const T) -> R) -> R {
+        f(self.0.get())
+    }
+
+    #[inline]
+    pub(crate) fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+        f(self.0.get())
+    }
+}
+
+/// Declares a `const fn`, except under loom whose primitives can't be created in a const context
+macro_rules! loom_const_fn {
+    ($(#[$attr:meta])* $vis:vis unsafe fn $($rest:tt)*) => {
+        #[cfg(not(loom))]
+        $(#[$attr])*
+        $vis const unsafe fn $($rest)*
+
+        #[cfg(loom)]
+        $(#[$attr])*
+        $vis unsafe fn $($rest)*
+    };
+    ($(#[$attr:meta])* $vis:vis fn $($rest:tt)*) => {
+        #[cfg(not(loom))]
+        $(#[$attr])*
+        $vis const fn $($rest)*
+
+        #[cfg(loom)]
+        $(#[$attr])*
+        $vis fn $($rest)*
+    };
+}
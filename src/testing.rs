@@ -0,0 +1,160 @@
+//! Scoped overrides of a [TaggedCell]'s value for tests, enabled by the `testing` feature
+use crate::TaggedCell;
+use std::{
+    cell::RefCell,
+    marker::PhantomData,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+thread_local! {
+    /// [OverrideId] of each cell overridden on this thread and its override value, innermost last
+    static OVERRIDES: RefCell<Vec<(usize, *const ())>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Next id handed out by [OverrideId::get_or_assign], 0 means unassigned
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// Identifies a cell in `OVERRIDES`, assigned the first time the cell is overridden. Unlike the
+/// cell's address, an id is never reused by another cell, so an entry left behind by a forgotten
+/// [OverrideGuard] can't be read back as a value of the wrong type
+pub(crate) struct OverrideId(AtomicUsize);
+
+impl OverrideId {
+    pub(crate) const fn new() -> Self {
+        OverrideId(AtomicUsize::new(0))
+    }
+
+    /// The cell's id, or 0 if it was never overridden
+    fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn get_or_assign(&self) -> usize {
+        let id = self.get();
+        if id != 0 {
+            return id;
+        }
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        match self
+            .0
+            .compare_exchange(0, id, Ordering::Relaxed, Ordering::Relaxed)
+        {
+            Ok(_) => id,
+            Err(assigned) => assigned,
+        }
+    }
+}
+
+/// Guard returned by [TaggedCell::override_for_scope]. The cell's own value is restored on the
+/// current thread when it is dropped. It cannot be sent to another thread, as the override only
+/// applies to the thread that created it
+#[must_use = "the override ends as soon as the guard is dropped"]
+pub struct OverrideGuard<'a, T, Tag> {
+    cell: &'a TaggedCell<T, Tag>,
+    value: &'a T,
+    thread: PhantomData<*const ()>,
+}
+
+impl<T, Tag> Drop for OverrideGuard<'_, T, Tag> {
+    fn drop(&mut self) {
+        let entry = (
+            self.cell.override_id.get(),
+            self.value as *const T as *const (),
+        );
+        let _ = OVERRIDES.try_with(|overrides| {
+            let mut overrides = overrides.borrow_mut();
+            if let Some(pos) = overrides.iter().rposition(|&e| e == entry) {
+                overrides.remove(pos);
+            }
+        });
+    }
+}
+
+impl<T, Tag> TaggedCell<T, Tag> {
+    /// Replace the cell's value with `value` on the current thread, until the returned guard is
+    /// dropped. Meant for swapping in a mock, such as a fake clock, for the duration of a test.
+    /// Requires the `testing` feature.
+    ///
+    /// While the override is active, the cell behaves on this thread as if it was initialized with
+    /// `value`: [get()][TaggedCell::get] returns it, [init()][TaggedCell::init] and
+    /// [init_async()][TaggedCell::init_async] return a tag without running their initializer,
+    /// and [set()][TaggedCell::set] hands its value back. Other threads keep seeing the cell's
+    /// own value. Overrides may be nested, the innermost one wins. The override value is shared,
+    /// so [get_mut()][TaggedCell::get_mut] never returns it, and panics instead while an override
+    /// is active on this thread. As the guard borrows the cell, this can only happen once the
+    /// guard has been forgotten.
+    ///
+    /// `value` is intentionally leaked, so references obtained from [get()][TaggedCell::get] stay
+    /// valid after the override ends. Its `Drop` implementation never runs, so a mock checking
+    /// its expectations when dropped, as `mockall` mocks do, checks nothing. A tag obtained during
+    /// the override doesn't prove the cell itself is initialized, so with this feature
+    /// [get()][TaggedCell::get] panics if such a tag is used on an uninitialized cell afterwards.
+    /// ```
+    /// use tagged_cell::tagged_cell;
+    ///
+    /// tagged_cell!{
+    ///     static NOW: TaggedCell<u64, _> = TaggedCell::new();
+    /// }
+    ///
+    /// fn timestamp() -> u64 {
+    ///     *NOW.get(NOW.init(|| 1_700_000_000))
+    /// }
+    ///
+    /// {
+    ///     let _fake = NOW.override_for_scope(42);
+    ///     assert_eq!(timestamp(), 42);
+    /// }
+    /// assert_eq!(timestamp(), 1_700_000_000);
+    /// ```
+    pub fn override_for_scope(&self, value: T) -> OverrideGuard<'_, T, Tag> {
+        let value: &T = Box::leak(Box::new(value));
+        let entry = (
+            self.override_id.get_or_assign(),
+            value as *const T as *const (),
+        );
+        OVERRIDES.with(|overrides| overrides.borrow_mut().push(entry));
+        OverrideGuard {
+            cell: self,
+            value,
+            thread: PhantomData,
+        }
+    }
+
+    /// The value overriding this cell on the current thread, if any
+    pub(crate) fn overridden(&self) -> Option<&T> {
+        let id = match self.override_id.get() {
+            0 => return None,
+            id => id,
+        };
+        OVERRIDES
+            .try_with(|overrides| {
+                let overrides = overrides.borrow();
+                let &(_, value) = overrides.iter().rev().find(|&&(cell, _)| cell == id)?;
+                // SAFETY: override values are leaked, and were created from a `T` for this cell,
+                // as no other cell shares its id
+                Some(unsafe { &*(value as *const T) })
+            })
+            .ok()
+            .flatten()
+    }
+
+    /// Check that [get_mut()][TaggedCell::get_mut] isn't used while the cell is overridden, as
+    /// it can't return the shared override value
+    pub(crate) fn assert_not_overridden(&self) {
+        assert!(
+            self.overridden().is_none(),
+            "get_mut called on TaggedCell `{}` while it is overridden on this thread",
+            core::any::type_name::<Tag>()
+        );
+    }
+
+    /// Check that a tag, which may have been obtained during an override, is used on an
+    /// initialized cell
+    pub(crate) fn assert_initialized(&self) {
+        assert!(
+            self.is_initialized(),
+            "tag for uninitialized TaggedCell `{}` used after its override ended or a reset",
+            core::any::type_name::<Tag>()
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Per-thread variant of [TaggedCell][crate::TaggedCell]
+use std::{cell::Cell, marker::PhantomData, thread::LocalKey};
+
+/// Storage for one thread's value of a [TaggedThreadLocal]
+#[doc(hidden)]
+pub type LocalSlot<T> = Cell<Option<&'static T>>;
+
+/// Thread-local counterpart to [TaggedCell][crate::TaggedCell]. Each thread initializes its own
+/// value, and receives a [LocalInit] tag that can only be used on that thread.
+/// Use [tagged_thread_local!][crate::tagged_thread_local] macro to make this struct
+///
+/// Each thread's value is allocated on first initialization, and is intentionally never dropped.
+/// This is what allows [get()][TaggedThreadLocal::get] to hand out a plain reference, without a
+/// closure like [LocalKey::with], as the reference may outlive the thread that created it.
+pub struct TaggedThreadLocal<T: 'static, Tag> {
+    key: &'static LocalKey<LocalSlot<T>>,
+    tag: PhantomData<Tag>,
+}
+
+/// A marker proving that the unique thread-local with tag `Tag` is initialized on the current
+/// thread. The only way to obtain it is by running [init()][TaggedThreadLocal::init] in the
+/// current thread, and it cannot be sent to or shared with another thread
+/// ```compile_fail
+/// use tagged_cell::tagged_thread_local;
+///
+/// tagged_thread_local!{
+///     static SCRATCH: TaggedThreadLocal<Vec<u8>, _> = TaggedThreadLocal::new();
+/// }
+///
+/// let tag = SCRATCH.init(Vec::new);
+/// std::thread::spawn(move || SCRATCH.get(tag).len());
+/// ```
+pub struct LocalInit<Tag> {
+    tag: PhantomData<(Tag, *const ())>,
+}
+
+// Implemented by hand, deriving would require `Tag: Copy`
+impl<Tag> Clone for LocalInit<Tag> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Tag> Copy for LocalInit<Tag> {}
+
+impl<T: 'static, Tag> TaggedThreadLocal<T, Tag> {
+    /// Internal method to create a thread-local from the slot declared by [tagged_thread_local!].
+    /// This relies on the user to define a unique 'Tag' type and slot for each call to new, and
+    /// thus is listed as unsafe
+    #[doc(hidden)]
+    pub const unsafe fn new(key: &'static LocalKey<LocalSlot<T>>) -> Self {
+        TaggedThreadLocal {
+            key,
+            tag: PhantomData,
+        }
+    }
+
+    /// Initialize the current thread's value, if not already initialized, using the provided
+    /// function or closure. Additionally returns a zero-sized tag bound to the current thread,
+    /// which is required to access the value.
+    ///
+    /// If the initializer itself initializes this thread-local, the value it stored is kept and
+    /// the outer initializer's value is dropped.
+    pub fn init<F>(&self, f: F) -> LocalInit<Tag>
+    where
+        F: FnOnce() -> T,
+    {
+        if self.key.with(Cell::get).is_none() {
+            let val = f();
+            self.key.with(|slot| {
+                if slot.get().is_none() {
+                    slot.set(Some(Box::leak(Box::new(val))));
+                }
+            });
+        }
+        LocalInit { tag: PhantomData }
+    }
+
+    /// Returns true if the current thread's value has been initialized
+    pub fn is_initialized(&self) -> bool {
+        self.key.with(Cell::get).is_some()
+    }
+
+    /// Get the current thread's value, requires a tag (obtained via [TaggedThreadLocal::init])
+    /// to perform the access
+    pub fn get(&self, _: LocalInit<Tag>) -> &T {
+        let val = self.key.with(Cell::get);
+        // SAFETY: LocalInit tag can't leave the thread, and proves that `init` has returned
+        // before on this thread, setting the slot.
+        unsafe { val.unwrap_unchecked() }
+    }
+}
+
+/// Safe macro for creating a [TaggedThreadLocal], mirrors [tagged_cell!][crate::tagged_cell].
+/// Requires the `std` feature
+#[macro_export]
+macro_rules! tagged_thread_local {
+    () => {};
+    (@static [$($attr:tt)*] $vis:vis $name:ident [$type:ty] [$tag:ty]) => {
+        $($attr)*
+        $vis static $name: $crate::TaggedThreadLocal<$type, $tag> = {
+            ::std::thread_local! {
+                static SLOT: $crate::LocalSlot<$type> = const { ::std::cell::Cell::new(None) };
+            }
+            unsafe { $crate::TaggedThreadLocal::new(&SLOT) }
+        };
+    };
+    (
+        $(#[$($attr:tt)*])*
+        $vis:vis static $name:ident : TaggedThreadLocal<$type:ty, _> = TaggedThreadLocal::new();
+        $($rest:tt)*
+    ) => {
+        $crate::__tag_type!([] [$(#[$($attr)*])*] $vis $name);
+        $crate::tagged_thread_local!(
+            @static [$(#[$($attr)*])*] $vis $name [$type] [$name::TagType]
+        );
+        $crate::tagged_thread_local!($($rest)*);
+    };
+    (
+        $(#[$($attr:tt)*])*
+        $vis:vis static $name:ident : TaggedThreadLocal<$type:ty, $tag:ident> = TaggedThreadLocal::new();
+        $($rest:tt)*
+    ) => {
+        $crate::__tag_type!([] [$(#[$($attr)*])*] $vis $name $tag);
+        $crate::tagged_thread_local!(@static [$(#[$($attr)*])*] $vis $name [$type] [$tag]);
+        $crate::tagged_thread_local!($($rest)*);
+    };
+}
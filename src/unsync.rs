@@ -0,0 +1,147 @@
+//! Single-threaded variant of [TaggedCell][crate::TaggedCell]
+use crate::{Init, Proves};
+use core::{
+    cell::{Cell, UnsafeCell},
+    convert::Infallible,
+    marker::PhantomData,
+    mem::MaybeUninit,
+};
+
+/// Single-threaded counterpart to [TaggedCell][crate::TaggedCell], for WASM, per-worker state or
+/// event loops where the cell is never shared between threads. It cannot be shared with other
+/// threads, so its state is a plain flag rather than an atomic, and initializing never
+/// synchronizes. It hands out the same [Init] proofs, and [get()][TaggedCell::get] performs no
+/// check at all.
+///
+/// As it isn't `Sync`, it can't be put in a `static` by [tagged_cell!][crate::tagged_cell], and
+/// is created with the unsafe [new()][TaggedCell::new] instead
+/// ```
+/// use tagged_cell::unsync::TaggedCell;
+///
+/// struct ScratchTag;
+///
+/// // SAFETY: no other cell is ever created with `ScratchTag`
+/// let scratch = unsafe { TaggedCell::<Vec<u8>, ScratchTag>::new() };
+/// let tag = scratch.init(|| vec![0; 16]);
+/// assert_eq!(scratch.get(tag).len(), 16);
+/// ```
+pub struct TaggedCell<T, Tag> {
+    initialized: Cell<bool>,
+    tag: PhantomData<Tag>,
+    data: UnsafeCell<MaybeUninit<T>>,
+}
+
+impl<T, Tag> TaggedCell<T, Tag> {
+    /// Create an uninitialized cell.
+    ///
+    /// # Safety
+    /// `Tag` must be unique to this cell, no other cell with the same tag may be created while it
+    /// is alive. Otherwise an [Init] obtained from one cell could be used to read the other before
+    /// it is initialized
+    pub const unsafe fn new() -> Self {
+        TaggedCell {
+            initialized: Cell::new(false),
+            tag: PhantomData,
+            data: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Initialize the cell, if not already initialized, using the provided function or closure.
+    /// Additionally returns a zero-sized tag, which is required to access the data.
+    ///
+    /// If the initializer panics the cell is left uninitialized, there is no other thread to
+    /// poison. If the initializer itself initializes this cell, the value it stored is kept and
+    /// the outer initializer's value is dropped.
+    pub fn init<F>(&self, f: F) -> Init<Tag>
+    where
+        F: FnOnce() -> T,
+    {
+        match self.try_init(|| Ok::<T, Infallible>(f())) {
+            Ok(tag) => tag,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Fallibly initialize the cell. On `Err` the cell is left uninitialized and the error is
+    /// handed back, so a later call runs its initializer again
+    pub fn try_init<F, E>(&self, f: F) -> Result<Init<Tag>, E>
+    where
+        F: FnOnce() -> Result<T, E>,
+    {
+        if !self.initialized.get() {
+            let val = f()?;
+            if !self.initialized.get() {
+                // SAFETY: the cell is uninitialized, so no reference to its data exists
+                unsafe { (*self.data.get()).write(val) };
+                self.initialized.set(true);
+            }
+        }
+        Ok(Init { tag: PhantomData })
+    }
+
+    /// Initialize the cell with a ready-made value. If the cell was already initialized, `value`
+    /// is handed back unchanged
+    pub fn set(&self, value: T) -> Result<Init<Tag>, T> {
+        if self.initialized.get() {
+            return Err(value);
+        }
+        Ok(self.init(|| value))
+    }
+
+    /// Get the data within the cell, requires a tag (obtained via [TaggedCell::init]) to perform
+    /// the access. A composite proof containing the cell's tag is also accepted, see [Proves]
+    pub fn get<P, I>(&self, _: P) -> &T
+    where
+        P: Proves<Tag, I>,
+    {
+        // SAFETY: Init tag proves that `init` has successfully returned before, initializing the
+        // cell, and the cell is never written again while shared
+        unsafe { (*self.data.get()).assume_init_ref() }
+    }
+
+    /// Get a mutable reference to the data within the cell, see
+    /// [TaggedCell::get_mut][crate::TaggedCell::get_mut]
+    pub fn get_mut<P, I>(&mut self, _: P) -> &mut T
+    where
+        P: Proves<Tag, I>,
+    {
+        // SAFETY: Init tag proves the cell is initialized, see `get`
+        unsafe { self.data.get_mut().assume_init_mut() }
+    }
+
+    /// If the cell has already been initialized, returns an [Init] tag along with the data
+    pub fn try_get(&self) -> Option<(Init<Tag>, &T)> {
+        if self.is_initialized() {
+            let tag = Init { tag: PhantomData };
+            Some((tag, self.get(tag)))
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the cell has been successfully initialized
+    pub fn is_initialized(&self) -> bool {
+        self.initialized.get()
+    }
+
+    /// Consume the cell, returning its data if it was initialized
+    pub fn into_inner(mut self) -> Option<T> {
+        if self.initialized.replace(false) {
+            // SAFETY: the cell was initialized, and is now marked as uninitialized so the value
+            // isn't dropped again
+            Some(unsafe { self.data.get_mut().assume_init_read() })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T, Tag> Drop for TaggedCell<T, Tag> {
+    fn drop(&mut self) {
+        if self.initialized.get() {
+            // SAFETY: the cell was initialized, and `&mut self` guarantees no references to the
+            // data are still alive
+            unsafe { self.data.get_mut().assume_init_drop() }
+        }
+    }
+}
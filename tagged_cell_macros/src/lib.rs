@@ -0,0 +1,170 @@
+//! Attribute macro for declaring [tagged_cell](https://docs.rs/tagged_cell) statics.
+//!
+//! Enable the `macros` feature of `tagged_cell` and import the attribute from
+//! `tagged_cell::macros::tagged_cell`, rather than depending on this crate directly.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    spanned::Spanned,
+    visit::{self, Visit},
+    Error, Ident, Item, ItemStatic, Result, StaticMutability, Token, Type, TypeInfer,
+};
+
+/// Declare a lazily initialized tagged cell. The static is written with the type of its data and
+/// the expression used to initialize it, and becomes a `TaggedLazy` with a freshly declared tag
+/// type. The tag is named after the static in camel case with a `Tag` suffix, so `FOO_BAR` gets
+/// `FooBarTag`, unless a name is given with `#[tagged_cell(tag = Name)]`.
+///
+/// Visibility and attributes are forwarded to the static, with the visibility and any `cfg`
+/// attributes also applied to the tag type.
+/// ```
+/// use tagged_cell::{macros::tagged_cell, Init};
+///
+/// #[tagged_cell]
+/// static TABLE: Vec<usize> = vec![0, 10, 20];
+///
+/// fn third(tag: Init<TableTag>) -> usize {
+///     TABLE.get(tag)[2]
+/// }
+///
+/// assert_eq!(third(TABLE.init()), 20);
+/// ```
+#[proc_macro_attribute]
+pub fn tagged_cell(args: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as Args);
+    let item = parse_macro_input!(item as Item);
+    expand(args, item)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Arguments accepted by the attribute, `#[tagged_cell]` or `#[tagged_cell(tag = Name)]`
+struct Args {
+    tag: Option<Ident>,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.is_empty() {
+            return Ok(Args { tag: None });
+        }
+        let key: Ident = input.parse()?;
+        if key != "tag" {
+            return Err(Error::new(
+                key.span(),
+                "unknown argument, expected `tag = Name`",
+            ));
+        }
+        input.parse::<Token![=]>()?;
+        let tag = input.parse()?;
+        if !input.is_empty() {
+            return Err(input.error("unexpected tokens after the tag name"));
+        }
+        Ok(Args { tag: Some(tag) })
+    }
+}
+
+fn expand(args: Args, item: Item) -> Result<TokenStream2> {
+    let item = match item {
+        Item::Static(item) => item,
+        item => {
+            return Err(Error::new(
+                item.span(),
+                "`#[tagged_cell]` can only be applied to a `static`",
+            ))
+        }
+    };
+    validate(&item)?;
+
+    let ItemStatic {
+        attrs,
+        vis,
+        ident,
+        ty,
+        expr,
+        ..
+    } = item;
+    let tag = args
+        .tag
+        .unwrap_or_else(|| format_ident!("{}Tag", camel_case(&ident), span = ident.span()));
+    let tag_doc = format!("Unique tag type of `{}`", ident);
+    let cfgs = attrs.iter().filter(|attr| attr.path().is_ident("cfg"));
+
+    Ok(quote! {
+        #(#cfgs)*
+        #[doc = #tag_doc]
+        #[allow(dead_code)]
+        #vis struct #tag;
+
+        #(#attrs)*
+        #vis static #ident: ::tagged_cell::TaggedLazy<#ty, #tag> = {
+            // Only called here, with the tag declared above. The data type comes from the
+            // static, as `fn() -> #ty` would reject elided lifetimes, and the expression is
+            // checked against it outside of an unsafe block
+            const fn lazy<T, Tag>(init: fn() -> T) -> ::tagged_cell::TaggedLazy<T, Tag> {
+                unsafe { ::tagged_cell::TaggedLazy::new(init) }
+            }
+            lazy(|| #expr)
+        };
+    })
+}
+
+/// Reject statics that can't become a tagged cell, pointing at the offending tokens
+fn validate(item: &ItemStatic) -> Result<()> {
+    if let StaticMutability::Mut(token) = &item.mutability {
+        return Err(Error::new(
+            token.span(),
+            "tagged cells are initialized through a shared reference, remove `mut`",
+        ));
+    }
+    if let Type::Path(path) = &*item.ty {
+        if let Some(last) = path.path.segments.last() {
+            if last.ident == "TaggedCell" || last.ident == "TaggedLazy" {
+                return Err(Error::new(
+                    item.ty.span(),
+                    "`#[tagged_cell]` declares the cell itself, write the type of its data instead",
+                ));
+            }
+        }
+    }
+
+    struct FindInfer(Option<TypeInfer>);
+    impl<'ast> Visit<'ast> for FindInfer {
+        fn visit_type_infer(&mut self, infer: &'ast TypeInfer) {
+            self.0.get_or_insert_with(|| infer.clone());
+        }
+        fn visit_type(&mut self, ty: &'ast Type) {
+            if self.0.is_none() {
+                visit::visit_type(self, ty);
+            }
+        }
+    }
+    let mut find = FindInfer(None);
+    find.visit_type(&item.ty);
+    if let Some(infer) = find.0 {
+        return Err(Error::new(
+            infer.span(),
+            "the type of a tagged cell must be written out in full, `_` is not allowed",
+        ));
+    }
+    Ok(())
+}
+
+/// Convert a static's name to camel case, `FOO_BAR` becomes `FooBar`
+fn camel_case(ident: &Ident) -> String {
+    ident
+        .to_string()
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap().to_ascii_uppercase();
+            std::iter::once(first)
+                .chain(chars.map(|c| c.to_ascii_lowercase()))
+                .collect::<String>()
+        })
+        .collect()
+}
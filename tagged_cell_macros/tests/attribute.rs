@@ -0,0 +1,12 @@
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/scopes.rs");
+    t.pass("tests/ui/attributes.rs");
+    t.compile_fail("tests/ui/not_static.rs");
+    t.compile_fail("tests/ui/static_mut.rs");
+    t.compile_fail("tests/ui/cell_type.rs");
+    t.compile_fail("tests/ui/infer_type.rs");
+    t.compile_fail("tests/ui/bad_args.rs");
+    t.compile_fail("tests/ui/wrong_type.rs");
+}
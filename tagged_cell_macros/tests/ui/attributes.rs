@@ -0,0 +1,21 @@
+#![deny(missing_docs)]
+//! Attributes are forwarded to the static, `cfg` also to the tag
+
+use tagged_cell::macros::tagged_cell;
+
+/// Documented, so `missing_docs` is satisfied
+#[tagged_cell]
+pub static DOCUMENTED: usize = 1;
+
+// only one of these exists, so neither the statics nor their tags collide
+#[cfg(any())]
+#[tagged_cell]
+static CONFIGURED: usize = 2;
+#[cfg(not(any()))]
+#[tagged_cell]
+static CONFIGURED: u8 = 3;
+
+fn main() {
+    assert_eq!(*DOCUMENTED.get(DOCUMENTED.init()), 1);
+    assert_eq!(*CONFIGURED.get(CONFIGURED.init()), 3u8);
+}
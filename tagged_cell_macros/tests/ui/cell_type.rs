@@ -0,0 +1,6 @@
+use tagged_cell::macros::tagged_cell;
+
+#[tagged_cell]
+static FOO: TaggedCell<usize, _> = TaggedCell::new();
+
+fn main() {}
@@ -0,0 +1,6 @@
+use tagged_cell::macros::tagged_cell;
+
+#[tagged_cell]
+static FOO: Vec<_> = vec![1, 2, 3];
+
+fn main() {}
@@ -0,0 +1,42 @@
+use tagged_cell::{macros::tagged_cell, Init};
+
+#[tagged_cell]
+static GLOBAL_TABLE: Vec<u8> = vec![1, 2, 3];
+
+// elided lifetimes are `'static`, as in any static
+#[tagged_cell]
+static GREETING: &str = "hello";
+
+mod config {
+    use tagged_cell::macros::tagged_cell;
+
+    #[tagged_cell(tag = Port)]
+    pub static PORT: u16 = 8080;
+}
+
+struct Server;
+
+impl Server {
+    fn name(&self) -> &'static str {
+        #[tagged_cell]
+        static NAME: String = String::from("server");
+
+        NAME.get(NAME.init())
+    }
+}
+
+fn port(tag: Init<config::Port>) -> u16 {
+    *config::PORT.get(tag)
+}
+
+fn main() {
+    #[tagged_cell]
+    static LOCAL: usize = 7;
+
+    let tag: Init<GlobalTableTag> = GLOBAL_TABLE.init();
+    assert_eq!(GLOBAL_TABLE.get(tag).len(), 3);
+    assert_eq!(*GREETING.get(GREETING.init()), "hello");
+    assert_eq!(port(config::PORT.init()), 8080);
+    assert_eq!(Server.name(), "server");
+    assert_eq!(*LOCAL.get(LOCAL.init()), 7);
+}
@@ -0,0 +1,6 @@
+use tagged_cell::macros::tagged_cell;
+
+#[tagged_cell]
+static mut FOO: usize = 0;
+
+fn main() {}
@@ -0,0 +1,105 @@
+//! Model tests of concurrent initialization, checking every interleaving with loom. Run with
+//! `RUSTFLAGS="--cfg loom" cargo test --release --test loom`
+#![cfg(loom)]
+use loom::{sync::Arc, thread};
+use std::panic::{self, AssertUnwindSafe};
+use tagged_cell::{InitError, PoisonPolicy, TaggedCell};
+
+struct Tag;
+
+/// Several values written by the initializer, so a reader seeing part of them would be caught
+#[derive(Debug, PartialEq)]
+struct Written {
+    id: usize,
+    data: Vec<usize>,
+}
+
+impl Written {
+    fn new(id: usize) -> Self {
+        Written {
+            id,
+            data: vec![id; 3],
+        }
+    }
+}
+
+#[test]
+fn racing_init() {
+    loom::model(|| {
+        let cell = Arc::new(unsafe { TaggedCell::<Written, Tag>::new() });
+        let handles: Vec<_> = (0..2)
+            .map(|id| {
+                let cell = cell.clone();
+                thread::spawn(move || cell.get(cell.init(|| Written::new(id))).id)
+            })
+            .collect();
+        let ids: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+        // exactly one initializer ran, and both threads see its value
+        assert_eq!(ids[0], ids[1]);
+        assert!(cell.is_initialized());
+    });
+}
+
+#[test]
+fn readers_see_written_value() {
+    loom::model(|| {
+        let cell = Arc::new(unsafe { TaggedCell::<Written, Tag>::new() });
+        let reader = {
+            let cell = cell.clone();
+            thread::spawn(move || {
+                if let Some((_, val)) = cell.try_get() {
+                    assert_eq!(*val, Written::new(1));
+                }
+            })
+        };
+
+        cell.init(|| Written::new(1));
+        reader.join().unwrap();
+    });
+}
+
+#[test]
+fn panic_during_init_poisons() {
+    loom::model(|| {
+        let cell = Arc::new(unsafe { TaggedCell::<Written, Tag>::new() });
+        let panicking = {
+            let cell = cell.clone();
+            thread::spawn(move || {
+                let res = panic::catch_unwind(AssertUnwindSafe(|| {
+                    cell.init(|| panic!("init failed"));
+                }));
+                assert!(res.is_err() || cell.is_initialized());
+            })
+        };
+
+        // either this thread initializes first, or it sees the poisoned cell, never half of both
+        match cell.try_init(|| Ok::<_, ()>(Written::new(2))) {
+            Ok(tag) => assert_eq!(*cell.get(tag), Written::new(2)),
+            Err(InitError::Poisoned(_)) => assert!(cell.is_poisoned()),
+            Err(InitError::Failed(())) => unreachable!(),
+        }
+        panicking.join().unwrap();
+    });
+}
+
+#[test]
+fn panic_during_init_retries() {
+    loom::model(|| {
+        let cell =
+            Arc::new(unsafe { TaggedCell::<Written, Tag>::with_policy(PoisonPolicy::Retry) });
+        let panicking = {
+            let cell = cell.clone();
+            thread::spawn(move || {
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+                    cell.init(|| panic!("init failed"));
+                }));
+            })
+        };
+
+        // whichever order the initializers run in, this one ends up storing the value
+        let tag = cell.init(|| Written::new(2));
+        assert_eq!(*cell.get(tag), Written::new(2));
+        panicking.join().unwrap();
+    });
+}
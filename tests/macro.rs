@@ -0,0 +1,10 @@
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/visibility.rs");
+    t.pass("tests/ui/multiple.rs");
+    t.pass("tests/ui/attributes.rs");
+    t.pass("tests/ui/named_tag.rs");
+    t.pass("tests/ui/lazy_elided.rs");
+    t.compile_fail("tests/ui/private.rs");
+}
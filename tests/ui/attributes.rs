@@ -0,0 +1,25 @@
+#![deny(missing_docs, non_upper_case_globals)]
+//! Attributes are forwarded to the generated statics
+
+use tagged_cell::tagged_cell;
+
+tagged_cell! {
+    /// Documented, so `missing_docs` is satisfied
+    pub static DOCUMENTED: TaggedCell<usize, _> = TaggedCell::new();
+
+    #[allow(non_upper_case_globals)]
+    static lowercase: TaggedCell<usize, _> = TaggedCell::new();
+
+    // only one of these exists, so neither the statics nor their tag modules collide
+    #[cfg(any())]
+    static CONFIGURED: TaggedCell<usize, _> = TaggedCell::new();
+    #[cfg(not(any()))]
+    static CONFIGURED: TaggedCell<u8, _> = TaggedCell::new();
+}
+
+fn main() {
+    assert_eq!(*DOCUMENTED.get(DOCUMENTED.init(|| 1)), 1);
+    assert_eq!(*lowercase.get(lowercase.init(|| 2)), 2);
+    let byte: u8 = *CONFIGURED.get(CONFIGURED.init(|| 3));
+    assert_eq!(byte, 3);
+}
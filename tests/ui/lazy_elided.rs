@@ -0,0 +1,13 @@
+//! Lazy cells accept types with elided lifetimes, like any static
+
+use tagged_cell::tagged_cell;
+
+tagged_cell! {
+    static NAME: TaggedCell<&str, _> = TaggedCell::lazy(|| "tagged");
+    static PARTS: TaggedCell<Vec<&str>, _> = TaggedCell::lazy(|| "a,b".split(',').collect());
+}
+
+fn main() {
+    assert_eq!(*NAME.get(NAME.init()), "tagged");
+    assert_eq!(*PARTS, ["a", "b"]);
+}
@@ -0,0 +1,18 @@
+use tagged_cell::{tagged_cell, tagged_thread_local, PoisonPolicy};
+
+tagged_cell! {
+    static A: TaggedCell<usize, _> = TaggedCell::new();
+    static B: TaggedCell<usize, _> = TaggedCell::with_policy(PoisonPolicy::Retry);
+    static C: TaggedCell<usize, _> = TaggedCell::lazy(|| 3);
+}
+
+tagged_thread_local! {
+    static D: TaggedThreadLocal<usize, _> = TaggedThreadLocal::new();
+    static E: TaggedThreadLocal<usize, _> = TaggedThreadLocal::new();
+}
+
+fn main() {
+    let sum = A.get(A.init(|| 1)) + B.get(B.init(|| 2)) + C.get(C.init());
+    assert_eq!(sum, 6);
+    assert_eq!(D.get(D.init(|| 4)) + E.get(E.init(|| 5)), 9);
+}
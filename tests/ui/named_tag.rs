@@ -0,0 +1,29 @@
+mod config {
+    use tagged_cell::{tagged_cell, tagged_thread_local};
+
+    tagged_cell! {
+        pub static PORT: TaggedCell<u16, PortTag> = TaggedCell::new();
+        pub static HOST: TaggedCell<&'static str, HostTag> = TaggedCell::lazy(|| "localhost");
+    }
+
+    tagged_thread_local! {
+        pub static SCRATCH: TaggedThreadLocal<Vec<u8>, ScratchTag> = TaggedThreadLocal::new();
+    }
+}
+
+use config::{HostTag, PortTag, ScratchTag, HOST, PORT, SCRATCH};
+use tagged_cell::{Init, LocalInit};
+
+// the named tags can be imported and used to require initialized statics
+fn address(port: Init<PortTag>, host: Init<HostTag>) -> String {
+    format!("{}:{}", HOST.get(host), PORT.get(port))
+}
+
+fn scratch_len(tag: LocalInit<ScratchTag>) -> usize {
+    SCRATCH.get(tag).len()
+}
+
+fn main() {
+    assert_eq!(address(PORT.init(|| 8080), HOST.init()), "localhost:8080");
+    assert_eq!(scratch_len(SCRATCH.init(|| vec![0; 4])), 4);
+}
@@ -0,0 +1,11 @@
+mod config {
+    use tagged_cell::tagged_cell;
+
+    tagged_cell! {
+        static SECRET: TaggedCell<u16, _> = TaggedCell::new();
+    }
+}
+
+fn main() {
+    config::SECRET.init(|| 0);
+}
@@ -0,0 +1,19 @@
+mod config {
+    use tagged_cell::tagged_cell;
+
+    tagged_cell! {
+        pub static PORT: TaggedCell<u16, _> = TaggedCell::new();
+        pub(crate) static HOST: TaggedCell<&'static str, _> = TaggedCell::lazy(|| "localhost");
+    }
+}
+
+// the tag module is as visible as the static, so proofs can be named outside the module
+fn port(tag: tagged_cell::Init<config::PORT::TagType>) -> u16 {
+    *config::PORT.get(tag)
+}
+
+fn main() {
+    let tag = config::PORT.init(|| 8080);
+    assert_eq!(port(tag), 8080);
+    assert_eq!(*config::HOST.get(config::HOST.init()), "localhost");
+}